@@ -1,14 +1,33 @@
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use std::sync::Arc;
 use anyhow::Context;
 use crate::domain::prova::Prova;
-use crate::domain::estado::EstadoSimuladoCompleto;
+use crate::domain::estado::{EstadoSimuladoCompleto, ModoSimulado};
 use crate::services::prova_service::ProvaService;
-use crate::services::simulado_service::{SimuladoService, SimuladoResumo, ResultadoSimulado};
+use crate::domain::resultado::{EstatisticaQuestao, ResultadoSimulado};
+use crate::domain::comparacao::ComparacaoTentativas;
+use crate::services::simulado_service::{SimuladoService, SimuladoResumo, CorrecaoQuestao, ItemLixeira, PoliticaRecuperacao};
+use crate::services::boletim::FormatoBoletim;
+use crate::services::exportacao::{FiltroExportacao, FormatoExportacao};
+use crate::services::edicao_service::{EdicaoService, EdicaoResumo};
+use crate::services::caderno_service::{CadernoService, CorrecaoTreino, QuestaoTreino};
+use crate::services::revisao_service::{CorrecaoRevisao, QuestaoRevisao, RevisaoService};
+use crate::services::backup_service::{BackupDisponivel, BackupService};
+use crate::services::sisu_service::{SimulacaoSisu, SisuService};
+use crate::domain::sisu::CursoSisu;
+use crate::domain::caderno::{ConfiguracaoCaderno, EntradaCaderno, FiltroCaderno};
+use crate::services::perfil_service::PerfilService;
+use crate::domain::perfil::Perfil;
 
 // === Wrappers para compartilhar serviços entre threads ===
 pub struct ProvaServiceWrapper(pub Arc<ProvaService>);
 pub struct SimuladoServiceWrapper(pub Arc<SimuladoService>);
+pub struct EdicaoServiceWrapper(pub Arc<EdicaoService>);
+pub struct CadernoServiceWrapper(pub Arc<CadernoService>);
+pub struct RevisaoServiceWrapper(pub Arc<RevisaoService>);
+pub struct SisuServiceWrapper(pub Arc<SisuService>);
+pub struct BackupServiceWrapper(pub Arc<BackupService>);
+pub struct PerfilServiceWrapper(pub Arc<PerfilService>);
 
 // === Comandos para Provas ===
 
@@ -34,26 +53,65 @@ pub async fn carregar_prova(
 #[tauri::command]
 pub async fn iniciar_simulado(
     service: State<'_, SimuladoServiceWrapper>,
+    edicoes: State<'_, EdicaoServiceWrapper>,
     prova_id: String,
     vestibular: String,
     ano: i32,
     duracao_minutos: i32,
+    edicao_id: Option<i64>,
+    modo: Option<ModoSimulado>,
 ) -> Result<i64, String> {
     println!("🔧 Iniciando simulado: prova_id={}, vestibular={}, ano={}", prova_id, vestibular, ano);
-    service.0
-        .iniciar_simulado(prova_id, vestibular, ano, duracao_minutos)
-        .map_err(|e| format!("Erro ao iniciar simulado: {}", e))
+    let modo = modo.unwrap_or_default();
+    if let Some(edicao_id) = edicao_id {
+        edicoes.0
+            .validar_vinculo(edicao_id, &prova_id, modo)
+            .map_err(|e| format!("Erro ao vincular simulado à edição: {}", e))?;
+    }
+    
+    let simulado_id = service.0
+        .iniciar_simulado(prova_id, vestibular, ano, duracao_minutos, modo)
+        .map_err(|e| format!("Erro ao iniciar simulado: {}", e))?;
+    
+    if let Some(edicao_id) = edicao_id {
+        if let Err(e) = edicoes.0.vincular_simulado(edicao_id, simulado_id) {
+            // Não deixa um simulado em andamento fora da edição pedida
+            let _ = service.0.descartar_simulado(simulado_id);
+            return Err(format!("Erro ao vincular simulado à edição: {}", e));
+        }
+    }
+    Ok(simulado_id)
 }
 
 #[tauri::command]
 pub async fn atualizar_tempo_simulado(
+    app: AppHandle,
     service: State<'_, SimuladoServiceWrapper>,
     simulado_id: i64,
 ) -> Result<(), String> {
      println!("🔧 Atualizando tempo para simulado {}", simulado_id);
-    service.0
+    let alertas = service.0
         .atualizar_tempo(simulado_id)
-        .map_err(|e| format!("Erro ao atualizar tempo: {}", e))
+        .map_err(|e| format!("Erro ao atualizar tempo: {}", e))?;
+    
+    // Avisos de "faltam N minutos" são empurrados ao frontend como evento, um por
+    // limiar. Só são verificados aqui: o frontend precisa chamar este heartbeat
+    for alerta in alertas {
+        app.emit("alerta-tempo", alerta)
+            .map_err(|e| format!("Erro ao emitir alerta de tempo: {}", e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn configurar_alertas_tempo(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_id: i64,
+    limiares_minutos: Vec<u32>,
+) -> Result<(), String> {
+    service.0
+        .configurar_alertas_tempo(simulado_id, limiares_minutos)
+        .map_err(|e| format!("Erro ao configurar alertas de tempo: {}", e))
 }
 
 #[tauri::command]
@@ -87,19 +145,32 @@ pub async fn obter_estado_simulado(
         .map_err(|e| format!("Erro ao obter estado do simulado: {}", e))
 }
 
-// Registrar resposta de questão
+// Registrar resposta de questão (no modo estudo devolve a correção)
 #[tauri::command]
 pub async fn responder_questao(
     service: State<'_, SimuladoServiceWrapper>,
     simulado_id: i64,
     questao_id: String,
     alternativa: Option<String>,
-) -> Result<(), String> {
+) -> Result<Option<CorrecaoQuestao>, String> {
     service.0
         .registrar_resposta(simulado_id, questao_id, alternativa)
         .map_err(|e| format!("Erro ao registrar resposta: {}", e))
 }
 
+// Riscar / desriscar alternativa (estratégia de eliminação)
+#[tauri::command]
+pub async fn alternar_alternativa_eliminada(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_id: i64,
+    questao_id: String,
+    alternativa: String,
+) -> Result<Vec<String>, String> {
+    service.0
+        .alternar_alternativa_eliminada(simulado_id, questao_id, alternativa)
+        .map_err(|e| format!("Erro ao riscar alternativa: {}", e))
+}
+
 // Avançar / voltar questão
 #[tauri::command]
 pub async fn avancar_questao(
@@ -132,28 +203,127 @@ pub async fn finalizar_simulado(
         .map_err(|e| format!("Erro ao finalizar simulado: {}", e))
 }
 
-// Obter resultado final
+// Desistir do simulado (em andamento ou pausado)
+#[tauri::command]
+pub async fn abandonar_simulado(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_id: i64,
+) -> Result<(), String> {
+    service.0
+        .abandonar_simulado(simulado_id)
+        .map_err(|e| format!("Erro ao abandonar simulado: {}", e))
+}
+
+// Abrir simulado finalizado para revisão (respostas bloqueadas, estado não muda)
+#[tauri::command]
+pub async fn revisar_simulado(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_id: i64,
+) -> Result<EstadoSimuladoCompleto, String> {
+    service.0
+        .revisar_simulado(simulado_id)
+        .map_err(|e| format!("Erro ao abrir revisão do simulado: {}", e))
+}
+
+// Obter resultado final (snapshot gravado na finalização; `recalcular` refaz com a prova atual)
 #[tauri::command]
 pub async fn obter_resultado(
     service: State<'_, SimuladoServiceWrapper>,
     simulado_id: i64,
+    recalcular: Option<bool>,
 ) -> Result<ResultadoSimulado, String> {
+    let resultado = if recalcular.unwrap_or(false) {
+        service.0.recalcular_resultado(simulado_id)
+    } else {
+        service.0.obter_resultado(simulado_id)
+    };
+    resultado.map_err(|e| format!("Erro ao calcular resultado: {}", e))
+}
+
+// Exportar boletim (HTML ou PDF) para o caminho escolhido pelo usuário
+#[tauri::command]
+pub async fn exportar_boletim(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_id: i64,
+    caminho: String,
+    formato: Option<FormatoBoletim>,
+) -> Result<(), String> {
+    service.0
+        .exportar_boletim(simulado_id, std::path::Path::new(&caminho), formato)
+        .map_err(|e| format!("Erro ao exportar boletim: {}", e))
+}
+
+// Exportar histórico de simulados (CSV ou JSON); devolve quantos foram exportados
+#[tauri::command]
+pub async fn exportar_historico(
+    service: State<'_, SimuladoServiceWrapper>,
+    caminho: String,
+    formato: Option<FormatoExportacao>,
+    filtro: Option<FiltroExportacao>,
+) -> Result<usize, String> {
+    service.0
+        .exportar_historico(std::path::Path::new(&caminho), formato, &filtro.unwrap_or_default())
+        .map_err(|e| format!("Erro ao exportar histórico: {}", e))
+}
+
+// Histórico de uma questão: vezes respondida, acertos e alternativas mais marcadas
+#[tauri::command]
+pub async fn estatisticas_questao(
+    service: State<'_, SimuladoServiceWrapper>,
+    prova_id: String,
+    questao_id: String,
+) -> Result<EstatisticaQuestao, String> {
+    service.0
+        .estatisticas_questao(&prova_id, &questao_id)
+        .map_err(|e| format!("Erro ao obter estatísticas da questão: {}", e))
+}
+
+// Comparar tentativas da mesma prova (evolução entre refações)
+#[tauri::command]
+pub async fn comparar_tentativas(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_ids: Vec<i64>,
+) -> Result<ComparacaoTentativas, String> {
     service.0
-        .calcular_resultado(simulado_id)
-        .map_err(|e| format!("Erro ao calcular resultado: {}", e))
+        .comparar_tentativas(simulado_ids)
+        .map_err(|e| format!("Erro ao comparar tentativas: {}", e))
+}
+
+// Corrigir prova feita em papel a partir da folha de respostas digitada
+#[tauri::command]
+pub async fn corrigir_folha_respostas(
+    service: State<'_, SimuladoServiceWrapper>,
+    prova_id: String,
+    respostas: String,
+    duracao_minutos: Option<u32>,
+) -> Result<ResultadoSimulado, String> {
+    service.0
+        .corrigir_folha_respostas(prova_id, &respostas, duracao_minutos)
+        .map_err(|e| format!("Erro ao corrigir folha de respostas: {}", e))
 }
 
 // Listar simulados anteriores
 #[tauri::command]
 pub async fn listar_simulados(
     service: State<'_, SimuladoServiceWrapper>,
+    incluir_abandonados: Option<bool>,
 ) -> Result<Vec<SimuladoResumo>, String> {
     service.0
-        .listar_simulados()
+        .listar_simulados(incluir_abandonados.unwrap_or(false))
         .map_err(|e| format!("Erro ao listar simulados: {}", e))
 }
 
-// Excluir simulado
+// Simulados em andamento ou pausados que podem ser continuados
+#[tauri::command]
+pub async fn listar_simulados_retomaveis(
+    service: State<'_, SimuladoServiceWrapper>,
+) -> Result<Vec<SimuladoResumo>, String> {
+    service.0
+        .listar_retomaveis()
+        .map_err(|e| format!("Erro ao listar simulados retomáveis: {}", e))
+}
+
+// Excluir simulado (vai para a lixeira)
 #[tauri::command]
 pub async fn excluir_simulado(
     service: State<'_, SimuladoServiceWrapper>,
@@ -164,6 +334,117 @@ pub async fn excluir_simulado(
         .map_err(|e| format!("Erro ao excluir simulado: {}", e))
 }
 
+#[tauri::command]
+pub async fn listar_lixeira(
+    service: State<'_, SimuladoServiceWrapper>,
+) -> Result<Vec<ItemLixeira>, String> {
+    service.0
+        .listar_lixeira()
+        .map_err(|e| format!("Erro ao listar lixeira: {}", e))
+}
+
+#[tauri::command]
+pub async fn restaurar_simulado(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_id: i64,
+) -> Result<(), String> {
+    service.0
+        .restaurar(simulado_id)
+        .map_err(|e| format!("Erro ao restaurar simulado: {}", e))
+}
+
+#[tauri::command]
+pub async fn excluir_simulado_definitivamente(
+    service: State<'_, SimuladoServiceWrapper>,
+    simulado_id: i64,
+) -> Result<(), String> {
+    service.0
+        .excluir_definitivamente(simulado_id)
+        .map_err(|e| format!("Erro ao excluir simulado definitivamente: {}", e))
+}
+
+#[tauri::command]
+pub async fn obter_dias_lixeira(
+    service: State<'_, SimuladoServiceWrapper>,
+) -> Result<u32, String> {
+    service.0
+        .dias_lixeira()
+        .map_err(|e| format!("Erro ao obter retenção da lixeira: {}", e))
+}
+
+#[tauri::command]
+pub async fn configurar_dias_lixeira(
+    service: State<'_, SimuladoServiceWrapper>,
+    dias: u32,
+) -> Result<(), String> {
+    service.0
+        .configurar_dias_lixeira(dias)
+        .map_err(|e| format!("Erro ao configurar lixeira: {}", e))
+}
+
+#[tauri::command]
+pub async fn obter_politica_recuperacao(
+    service: State<'_, SimuladoServiceWrapper>,
+) -> Result<PoliticaRecuperacao, String> {
+    service.0
+        .politica_recuperacao()
+        .map_err(|e| format!("Erro ao obter política de recuperação: {}", e))
+}
+
+// Vale na próxima abertura do app, para simulados interrompidos por fechamento inesperado
+#[tauri::command]
+pub async fn configurar_politica_recuperacao(
+    service: State<'_, SimuladoServiceWrapper>,
+    politica: PoliticaRecuperacao,
+) -> Result<(), String> {
+    service.0
+        .configurar_politica_recuperacao(politica)
+        .map_err(|e| format!("Erro ao configurar política de recuperação: {}", e))
+}
+
+// === Comandos para Edições (ex: ENEM dia 1 + dia 2) ===
+
+#[tauri::command]
+pub async fn criar_edicao(
+    service: State<'_, EdicaoServiceWrapper>,
+    vestibular: String,
+    ano: i32,
+) -> Result<i64, String> {
+    service.0
+        .criar_edicao(vestibular, ano)
+        .map_err(|e| format!("Erro ao criar edição: {}", e))
+}
+
+#[tauri::command]
+pub async fn vincular_simulado_edicao(
+    service: State<'_, EdicaoServiceWrapper>,
+    edicao_id: i64,
+    simulado_id: i64,
+) -> Result<(), String> {
+    service.0
+        .vincular_simulado(edicao_id, simulado_id)
+        .map_err(|e| format!("Erro ao vincular simulado à edição: {}", e))
+}
+
+#[tauri::command]
+pub async fn obter_edicao(
+    service: State<'_, EdicaoServiceWrapper>,
+    edicao_id: i64,
+) -> Result<EdicaoResumo, String> {
+    service.0
+        .obter_edicao(edicao_id)
+        .map_err(|e| format!("Erro ao obter edição: {}", e))
+}
+
+#[tauri::command]
+pub async fn listar_edicoes(
+    service: State<'_, EdicaoServiceWrapper>,
+) -> Result<Vec<EdicaoResumo>, String> {
+    service.0
+        .listar_edicoes()
+        .map_err(|e| format!("Erro ao listar edições: {}", e))
+}
+
 // Comando para verificar se questão existe
 #[tauri::command]
 pub async fn questao_existe(
@@ -180,4 +461,241 @@ pub async fn questao_existe(
     prova_service
         .questao_existe(&prova_id, &questao_id)
         .map_err(|e| format!("Erro ao verificar questão: {}", e))
-}
\ No newline at end of file
+}
+
+// === Comandos para o Caderno de Erros ===
+
+#[tauri::command]
+pub async fn listar_caderno_erros(
+    service: State<'_, CadernoServiceWrapper>,
+    filtro: Option<FiltroCaderno>,
+) -> Result<Vec<EntradaCaderno>, String> {
+    service.0
+        .listar(&filtro.unwrap_or_default())
+        .map_err(|e| format!("Erro ao listar caderno de erros: {}", e))
+}
+
+#[tauri::command]
+pub async fn iniciar_treino_caderno(
+    service: State<'_, CadernoServiceWrapper>,
+    filtro: Option<FiltroCaderno>,
+    limite: Option<usize>,
+) -> Result<Vec<QuestaoTreino>, String> {
+    service.0
+        .iniciar_treino(&filtro.unwrap_or_default(), limite)
+        .map_err(|e| format!("Erro ao iniciar treino do caderno: {}", e))
+}
+
+#[tauri::command]
+pub async fn responder_treino_caderno(
+    service: State<'_, CadernoServiceWrapper>,
+    entrada_id: i64,
+    alternativa: String,
+) -> Result<CorrecaoTreino, String> {
+    service.0
+        .responder_treino(entrada_id, alternativa)
+        .map_err(|e| format!("Erro ao responder treino do caderno: {}", e))
+}
+
+#[tauri::command]
+pub async fn obter_configuracao_caderno(
+    service: State<'_, CadernoServiceWrapper>,
+) -> Result<ConfiguracaoCaderno, String> {
+    service.0
+        .obter_configuracao()
+        .map_err(|e| format!("Erro ao obter configuração do caderno: {}", e))
+}
+
+#[tauri::command]
+pub async fn configurar_caderno(
+    service: State<'_, CadernoServiceWrapper>,
+    configuracao: ConfiguracaoCaderno,
+) -> Result<(), String> {
+    service.0
+        .configurar(configuracao)
+        .map_err(|e| format!("Erro ao configurar caderno: {}", e))
+}
+
+// === Comandos da Revisão Espaçada ===
+
+// Questões pendentes de revisão hoje (inclui as atrasadas)
+#[tauri::command]
+pub async fn revisao_do_dia(
+    service: State<'_, RevisaoServiceWrapper>,
+    limite: Option<usize>,
+) -> Result<Vec<QuestaoRevisao>, String> {
+    service.0
+        .revisao_do_dia(limite)
+        .map_err(|e| format!("Erro ao montar revisão do dia: {}", e))
+}
+
+#[tauri::command]
+pub async fn responder_revisao(
+    service: State<'_, RevisaoServiceWrapper>,
+    cartao_id: i64,
+    alternativa: String,
+    nota: Option<u8>,
+) -> Result<CorrecaoRevisao, String> {
+    service.0
+        .responder_revisao(cartao_id, alternativa, nota)
+        .map_err(|e| format!("Erro ao responder revisão: {}", e))
+}
+
+#[tauri::command]
+pub async fn adicionar_questoes_revisao(
+    service: State<'_, RevisaoServiceWrapper>,
+    prova_id: String,
+    questao_ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    service.0
+        .adicionar_questoes(&prova_id, questao_ids)
+        .map_err(|e| format!("Erro ao adicionar questões à revisão: {}", e))
+}
+
+// === Comandos do SISU ===
+
+// Importa a base de cursos (pesos e notas de corte) a partir de um arquivo local
+#[tauri::command]
+pub async fn importar_cursos_sisu(
+    service: State<'_, SisuServiceWrapper>,
+    caminho: String,
+) -> Result<usize, String> {
+    service.0
+        .importar_cursos(std::path::Path::new(&caminho))
+        .map_err(|e| format!("Erro ao importar cursos do SISU: {}", e))
+}
+
+#[tauri::command]
+pub async fn listar_cursos_sisu(
+    service: State<'_, SisuServiceWrapper>,
+    busca: Option<String>,
+) -> Result<Vec<CursoSisu>, String> {
+    service.0
+        .listar_cursos(busca.as_deref())
+        .map_err(|e| format!("Erro ao listar cursos do SISU: {}", e))
+}
+
+#[tauri::command]
+pub async fn simular_sisu(
+    service: State<'_, SisuServiceWrapper>,
+    curso_ids: Vec<String>,
+    notas: Option<std::collections::HashMap<String, f64>>,
+    edicao_id: Option<i64>,
+    redacao: Option<f64>,
+) -> Result<SimulacaoSisu, String> {
+    service.0
+        .simular(&curso_ids, notas, edicao_id, redacao)
+        .map_err(|e| format!("Erro ao simular SISU: {}", e))
+}
+
+// === Comandos de Backup ===
+
+// Exporta uma cópia consistente do banco para o caminho escolhido
+#[tauri::command]
+pub async fn exportar_backup(
+    service: State<'_, BackupServiceWrapper>,
+    caminho: String,
+) -> Result<(), String> {
+    service.0
+        .exportar(std::path::Path::new(&caminho))
+        .map_err(|e| format!("Erro ao exportar backup: {}", e))
+}
+
+// Restaura o banco a partir de um backup (validado antes)
+#[tauri::command]
+pub async fn restaurar_backup(
+    service: State<'_, BackupServiceWrapper>,
+    caminho: String,
+) -> Result<(), String> {
+    service.0
+        .restaurar(std::path::Path::new(&caminho))
+        .map_err(|e| format!("Erro ao restaurar backup: {}", e))
+}
+
+#[tauri::command]
+pub async fn listar_backups(
+    service: State<'_, BackupServiceWrapper>,
+) -> Result<Vec<BackupDisponivel>, String> {
+    service.0
+        .listar_backups()
+        .map_err(|e| format!("Erro ao listar backups: {}", e))
+}
+
+// === Comandos de Perfis ===
+
+#[tauri::command]
+pub async fn listar_perfis(
+    service: State<'_, PerfilServiceWrapper>,
+) -> Result<Vec<Perfil>, String> {
+    service.0
+        .listar()
+        .map_err(|e| format!("Erro ao listar perfis: {}", e))
+}
+
+#[tauri::command]
+pub async fn obter_perfil_ativo(
+    service: State<'_, PerfilServiceWrapper>,
+) -> Result<Perfil, String> {
+    service.0
+        .ativo()
+        .map_err(|e| format!("Erro ao obter perfil ativo: {}", e))
+}
+
+#[tauri::command]
+pub async fn criar_perfil(
+    service: State<'_, PerfilServiceWrapper>,
+    nome: String,
+    pin: Option<String>,
+) -> Result<Perfil, String> {
+    service.0
+        .criar(&nome, pin.as_deref())
+        .map_err(|e| format!("Erro ao criar perfil: {}", e))
+}
+
+#[tauri::command]
+pub async fn renomear_perfil(
+    service: State<'_, PerfilServiceWrapper>,
+    perfil_id: i64,
+    nome: String,
+    pin: Option<String>,
+) -> Result<Perfil, String> {
+    service.0
+        .renomear(perfil_id, &nome, pin.as_deref())
+        .map_err(|e| format!("Erro ao renomear perfil: {}", e))
+}
+
+// Sem `novo_pin`, o PIN é removido
+#[tauri::command]
+pub async fn definir_pin_perfil(
+    service: State<'_, PerfilServiceWrapper>,
+    perfil_id: i64,
+    pin_atual: Option<String>,
+    novo_pin: Option<String>,
+) -> Result<Perfil, String> {
+    service.0
+        .definir_pin(perfil_id, pin_atual.as_deref(), novo_pin.as_deref())
+        .map_err(|e| format!("Erro ao definir PIN do perfil: {}", e))
+}
+
+#[tauri::command]
+pub async fn trocar_perfil(
+    service: State<'_, PerfilServiceWrapper>,
+    perfil_id: i64,
+    pin: Option<String>,
+) -> Result<Perfil, String> {
+    service.0
+        .trocar(perfil_id, pin.as_deref())
+        .map_err(|e| format!("Erro ao trocar de perfil: {}", e))
+}
+
+// Apaga o perfil com todos os simulados, edições, caderno e revisões dele
+#[tauri::command]
+pub async fn excluir_perfil(
+    service: State<'_, PerfilServiceWrapper>,
+    perfil_id: i64,
+    pin: Option<String>,
+) -> Result<(), String> {
+    service.0
+        .excluir(perfil_id, pin.as_deref())
+        .map_err(|e| format!("Erro ao excluir perfil: {}", e))
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use rusqlite::{Row, Result as RusqliteResult};
+
+/// Questão errada guardada no caderno de erros. Há uma entrada por questão de
+/// cada prova; errar de novo atualiza a entrada e zera os acertos seguidos.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EntradaCaderno {
+    pub id: i64,
+    pub prova_id: String,
+    pub questao_id: String,
+    pub area_id: String,
+    /// Simulado em que a questão foi errada pela última vez
+    pub simulado_id: i64,
+    /// Alternativa escolhida no último erro; `None` quando ficou em branco
+    pub alternativa_marcada: Option<String>,
+    pub errado_em: DateTime<Utc>,
+    pub vezes_errada: u32,
+    pub acertos_seguidos: u32,
+}
+
+impl EntradaCaderno {
+    pub fn from_row(row: &Row<'_>) -> RusqliteResult<Self> {
+        Ok(EntradaCaderno {
+            id: row.get("id")?,
+            prova_id: row.get("prova_id")?,
+            questao_id: row.get("questao_id")?,
+            area_id: row.get("area_id")?,
+            simulado_id: row.get("simulado_id")?,
+            alternativa_marcada: row.get("alternativa_marcada")?,
+            errado_em: row.get("errado_em")?,
+            vezes_errada: row.get("vezes_errada")?,
+            acertos_seguidos: row.get("acertos_seguidos")?,
+        })
+    }
+}
+
+/// Filtro para listar ou treinar o caderno; campos vazios não filtram
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FiltroCaderno {
+    #[serde(default)]
+    pub area_id: Option<String>,
+    #[serde(default)]
+    pub prova_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfiguracaoCaderno {
+    /// Questões deixadas em branco também entram no caderno
+    pub incluir_brancos: bool,
+    /// Acertos seguidos necessários para a questão sair do caderno
+    pub acertos_para_remover: u32,
+}
+
+impl Default for ConfiguracaoCaderno {
+    fn default() -> Self {
+        Self { incluir_brancos: false, acertos_para_remover: 3 }
+    }
+}
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use crate::domain::resultado::ResultadoSimulado;
+
+/// Como a resposta de uma questão mudou de uma tentativa para outra.
+/// Questão em branco conta como errada.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SituacaoQuestao {
+    Corrigida,
+    Quebrada,
+    ContinuaErrada,
+    ContinuaCerta,
+}
+
+impl SituacaoQuestao {
+    pub fn entre(antes: bool, depois: bool) -> Self {
+        match (antes, depois) {
+            (false, true) => Self::Corrigida,
+            (true, false) => Self::Quebrada,
+            (false, false) => Self::ContinuaErrada,
+            (true, true) => Self::ContinuaCerta,
+        }
+    }
+}
+
+/// Uma tentativa a comparar: o resultado gravado e os dados do simulado
+pub struct Tentativa {
+    pub iniciado_em: Option<DateTime<Utc>>,
+    pub finalizado_em: Option<DateTime<Utc>>,
+    /// Tempo ativo em segundos; `None` quando não foi registrado
+    pub tempo_segundos: Option<u32>,
+    pub resultado: ResultadoSimulado,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TentativaComparada {
+    pub simulado_id: i64,
+    pub iniciado_em: Option<String>,
+    pub finalizado_em: Option<String>,
+    pub pontuacao: f64,
+    pub acertos: usize,
+    pub tempo_segundos: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComparacaoQuestao {
+    pub questao_id: String,
+    pub numero: u32,
+    pub area_id: String,
+    pub gabarito: String,
+    /// Resposta em cada tentativa, na ordem cronológica
+    pub respostas: Vec<Option<String>>,
+    /// Mudança entre cada par de tentativas consecutivas
+    pub transicoes: Vec<SituacaoQuestao>,
+    /// Mudança da primeira para a última tentativa
+    pub situacao: SituacaoQuestao,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeltaArea {
+    pub area_id: String,
+    /// Percentual de acertos da área em cada tentativa
+    pub percentuais: Vec<f64>,
+    pub delta: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComparacaoTentativas {
+    pub prova_id: String,
+    pub tentativas: Vec<TentativaComparada>,
+    pub delta_pontuacao: f64,
+    pub delta_acertos: i64,
+    /// Diferença de tempo entre a última e a primeira tentativa, se ambas registraram tempo
+    pub delta_tempo_segundos: Option<i64>,
+    pub corrigidas: usize,
+    pub quebradas: usize,
+    pub continuam_erradas: usize,
+    pub continuam_certas: usize,
+    pub areas: Vec<DeltaArea>,
+    pub questoes: Vec<ComparacaoQuestao>,
+}
+
+/// Compara tentativas da mesma prova. Espera ao menos duas, em ordem cronológica.
+pub fn comparar(prova_id: &str, tentativas: &[Tentativa]) -> ComparacaoTentativas {
+    let primeira = &tentativas[0].resultado;
+    let ultima = &tentativas[tentativas.len() - 1].resultado;
+
+    let questoes: Vec<ComparacaoQuestao> = primeira.detalhes.iter()
+        .map(|detalhe| {
+            let acertos: Vec<bool> = tentativas.iter()
+                .map(|t| t.resultado.detalhes.iter()
+                    .find(|d| d.questao_id == detalhe.questao_id)
+                    .is_some_and(|d| d.acertou))
+                .collect();
+            let respostas = tentativas.iter()
+                .map(|t| t.resultado.detalhes.iter()
+                    .find(|d| d.questao_id == detalhe.questao_id)
+                    .and_then(|d| d.resposta_usuario.clone()))
+                .collect();
+            
+            ComparacaoQuestao {
+                questao_id: detalhe.questao_id.clone(),
+                numero: detalhe.numero,
+                area_id: detalhe.area_id.clone(),
+                gabarito: detalhe.gabarito.clone(),
+                respostas,
+                transicoes: acertos.windows(2).map(|par| SituacaoQuestao::entre(par[0], par[1])).collect(),
+                situacao: SituacaoQuestao::entre(acertos[0], acertos[acertos.len() - 1]),
+            }
+        })
+        .collect();
+
+    let contar = |situacao: SituacaoQuestao| questoes.iter().filter(|q| q.situacao == situacao).count();
+
+    let areas = primeira.areas.iter()
+        .map(|area| {
+            let percentuais: Vec<f64> = tentativas.iter()
+                .map(|t| t.resultado.areas.iter()
+                    .find(|a| a.area_id == area.area_id)
+                    .map_or(0.0, |a| a.percentual))
+                .collect();
+            DeltaArea {
+                area_id: area.area_id.clone(),
+                delta: percentuais[percentuais.len() - 1] - percentuais[0],
+                percentuais,
+            }
+        })
+        .collect();
+
+    let tempo_inicial = tentativas[0].tempo_segundos;
+    let tempo_final = tentativas[tentativas.len() - 1].tempo_segundos;
+
+    ComparacaoTentativas {
+        prova_id: prova_id.to_string(),
+        tentativas: tentativas.iter()
+            .map(|t| TentativaComparada {
+                simulado_id: t.resultado.simulado_id,
+                iniciado_em: t.iniciado_em.map(|dt| dt.to_rfc3339()),
+                finalizado_em: t.finalizado_em.map(|dt| dt.to_rfc3339()),
+                pontuacao: t.resultado.pontuacao,
+                acertos: t.resultado.acertos,
+                tempo_segundos: t.tempo_segundos,
+            })
+            .collect(),
+        delta_pontuacao: ultima.pontuacao - primeira.pontuacao,
+        delta_acertos: ultima.acertos as i64 - primeira.acertos as i64,
+        delta_tempo_segundos: tempo_inicial.zip(tempo_final).map(|(antes, depois)| depois as i64 - antes as i64),
+        corrigidas: contar(SituacaoQuestao::Corrigida),
+        quebradas: contar(SituacaoQuestao::Quebrada),
+        continuam_erradas: contar(SituacaoQuestao::ContinuaErrada),
+        continuam_certas: contar(SituacaoQuestao::ContinuaCerta),
+        areas,
+        questoes,
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use rusqlite::{Row, Result as RusqliteResult};
+
+/// Edição de um vestibular aplicado em mais de um dia (ex: ENEM 2022, dias 1 e 2).
+/// Agrupa os simulados de cada dia para que o resultado seja lido como um todo.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Edicao {
+    pub id: i64,
+    pub vestibular: String,
+    pub ano: i32,
+    pub criada_em: Option<DateTime<Utc>>,
+}
+
+/// Vínculo entre uma edição e o simulado feito para um de seus dias
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VinculoEdicao {
+    pub edicao_id: i64,
+    pub dia: u8,
+    pub simulado_id: i64,
+}
+
+impl Edicao {
+    pub fn from_row(row: &Row<'_>) -> RusqliteResult<Self> {
+        Ok(Edicao {
+            id: row.get("id")?,
+            vestibular: row.get("vestibular")?,
+            ano: row.get("ano")?,
+            criada_em: row.get("criada_em")?,
+        })
+    }
+}
+
+impl VinculoEdicao {
+    pub fn from_row(row: &Row<'_>) -> RusqliteResult<Self> {
+        Ok(VinculoEdicao {
+            edicao_id: row.get("edicao_id")?,
+            dia: row.get("dia")?,
+            simulado_id: row.get("simulado_id")?,
+        })
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -9,6 +9,8 @@ pub enum EstadoSimulado {
     Pausado,
     Finalizado,
     FinalizadoPorTempo,
+    /// Tentativa desistida pelo aluno; não entra em resultados nem estatísticas
+    Abandonado,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -18,16 +20,41 @@ pub enum ModoTempo {
     Livre,
 }
 
+/// Modo escolhido ao iniciar: prova cronometrada ou treino com correção imediata
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ModoSimulado {
+    #[default]
+    Prova,
+    Estudo,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TempoSimulado {
     pub limite_minutos: u16,
+    /// Tempo ativo total: `acumulado_segundos` + duração do intervalo em curso
     pub decorrido_segundos: u32,
+    /// Soma dos intervalos ativos já encerrados (por pausa ou finalização)
+    #[serde(default)]
+    pub acumulado_segundos: u32,
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub inicio: Option<chrono::DateTime<chrono::Utc>>,
+    /// Início (relógio de parede) do intervalo ativo em curso; `None` fora de `EmAndamento`
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub inicio_intervalo: Option<chrono::DateTime<chrono::Utc>>,
+    /// Valor de `decorrido_segundos` quando o aluno chegou à questão atual
+    #[serde(default)]
+    pub questao_desde_segundos: u32,
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub pausado_em: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub finalizado_em: Option<chrono::DateTime<chrono::Utc>>,
+    /// Último sinal de vida recebido do frontend enquanto o simulado estava em andamento
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub ultimo_heartbeat: Option<chrono::DateTime<chrono::Utc>>,
+    /// Limiares (em minutos restantes) cujo alerta já foi emitido
+    #[serde(default)]
+    pub alertas_disparados: Vec<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -41,15 +68,33 @@ pub struct ProgressoSimulado {
 pub struct ConfiguracoesSimulado {
     pub permitir_ultrapassar_tempo: bool,
     pub mostrar_gabarito_ao_final: bool,
+    /// Minutos restantes em que o backend avisa o aluno (ex: [30, 5])
+    #[serde(default = "alertas_padrao")]
+    pub alertas_minutos: Vec<u32>,
+}
+
+fn alertas_padrao() -> Vec<u32> {
+    vec![30, 5]
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EstadoSimuladoCompleto {
+    /// Versão do formato gravado (ver `domain::versao_estado`)
+    pub versao: u32,
     pub estado: EstadoSimulado,
+    #[serde(default)]
+    pub modo: ModoSimulado,
     pub modo_tempo: ModoTempo,
     pub tempo: TempoSimulado,
     pub progresso: ProgressoSimulado,
-    pub respostas: HashMap<String, Option<String>>, // "Q01" => Some("A") ou None
+    /// "Q01" => Some("A") ou None. Cópia para a interface: cada marcação também vira
+    /// uma linha da tabela `resposta`, gravada na mesma transação. A correção (e com ela
+    /// o resultado, a exportação, a comparação e as edições) lê a tabela, não este campo
+    pub respostas: HashMap<String, Option<String>>,
+    #[serde(default)]
+    pub eliminadas: HashMap<String, BTreeSet<String>>, // "Q01" => {"B", "E"} riscadas pelo aluno
+    #[serde(default)]
+    pub tempo_questoes: HashMap<String, u32>, // "Q01" => segundos ativos na questão
     pub configuracoes: ConfiguracoesSimulado,
 }
 
@@ -69,6 +114,24 @@ impl EstadoSimulado {
     pub const fn esta_ativo(&self) -> bool {
         matches!(self, Self::EmAndamento | Self::Pausado)
     }
+    
+    pub const fn esta_finalizado(&self) -> bool {
+        matches!(self, Self::Finalizado | Self::FinalizadoPorTempo)
+    }
+    
+    pub const fn pode_responder(&self) -> bool {
+        matches!(self, Self::EmAndamento)
+    }
+    
+    pub const fn pode_abandonar(&self) -> bool {
+        self.esta_ativo()
+    }
+    
+    /// A revisão é só uma leitura do simulado finalizado: navega, mas não aceita
+    /// respostas, e não muda o estado gravado
+    pub const fn pode_revisar(&self) -> bool {
+        self.esta_finalizado()
+    }
 }
 
 impl ProgressoSimulado {
@@ -101,14 +164,21 @@ impl ProgressoSimulado {
 impl Default for EstadoSimuladoCompleto {
     fn default() -> Self {
         Self {
+            versao: crate::domain::versao_estado::VERSAO_ESTADO,
             estado: EstadoSimulado::NaoIniciado,
+            modo: ModoSimulado::Prova,
             modo_tempo: ModoTempo::Cronometrado,
             tempo: TempoSimulado {
                 limite_minutos: 0,
                 decorrido_segundos: 0,
+                acumulado_segundos: 0,
                 inicio: None,
+                inicio_intervalo: None,
+                questao_desde_segundos: 0,
                 pausado_em: None,
                 finalizado_em: None,
+                ultimo_heartbeat: None,
+                alertas_disparados: Vec::new(),
             },
             progresso: ProgressoSimulado {
                 questao_atual: "Q01".to_string(),
@@ -116,10 +186,9 @@ impl Default for EstadoSimuladoCompleto {
                 total: 0,
             },
             respostas: std::collections::HashMap::new(),
-            configuracoes: ConfiguracoesSimulado {
-                permitir_ultrapassar_tempo: true,
-                mostrar_gabarito_ao_final: true,
-            },
+            eliminadas: std::collections::HashMap::new(),
+            tempo_questoes: std::collections::HashMap::new(),
+            configuracoes: ConfiguracoesSimulado::default(),
         }
     }
 }
@@ -129,9 +198,14 @@ impl Default for TempoSimulado {
         Self {
             limite_minutos: 0,
             decorrido_segundos: 0,
+            acumulado_segundos: 0,
             inicio: None,
+            inicio_intervalo: None,
+            questao_desde_segundos: 0,
             pausado_em: None,
             finalizado_em: None,
+            ultimo_heartbeat: None,
+            alertas_disparados: Vec::new(),
         }
     }
 }
@@ -151,6 +225,7 @@ impl Default for ConfiguracoesSimulado {
         Self {
             permitir_ultrapassar_tempo: true,
             mostrar_gabarito_ao_final: true,
+            alertas_minutos: alertas_padrao(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Estatísticas oficiais de uma prova (ex: extraídas dos microdados do INEP),
+/// lidas de `estatisticas.json` ao lado do `prova.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EstatisticasProva {
+    /// De onde vieram os números (ex: "Microdados ENEM 2022 - INEP")
+    #[serde(default)]
+    pub fonte: Option<String>,
+    /// Distribuição dos candidatos por número de acertos na prova inteira
+    #[serde(default)]
+    pub distribuicao: Vec<FaixaDistribuicao>,
+    /// Distribuição por número de acertos em cada área (`area_id` => faixas)
+    #[serde(default)]
+    pub areas: HashMap<String, Vec<FaixaDistribuicao>>,
+    /// Taxa de acerto nacional por questão (`Q01` => percentual de 0 a 100)
+    #[serde(default)]
+    pub questoes: HashMap<String, f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FaixaDistribuicao {
+    pub acertos: usize,
+    /// Percentual dos candidatos que tiveram exatamente esse número de acertos
+    pub percentual_candidatos: f64,
+}
+
+/// Posição do aluno frente aos candidatos da edição oficial
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Comparativo {
+    pub fonte: Option<String>,
+    /// Percentual de candidatos com desempenho inferior ao do aluno
+    pub percentil: Option<f64>,
+    pub areas: Vec<PercentilArea>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PercentilArea {
+    pub area_id: String,
+    pub percentil: f64,
+}
+
+/// Percentil de quem teve `acertos` na distribuição: os candidatos abaixo mais
+/// metade dos empatados. As faixas não precisam somar exatamente 100.
+pub fn percentil(distribuicao: &[FaixaDistribuicao], acertos: usize) -> Option<f64> {
+    let total: f64 = distribuicao.iter().map(|f| f.percentual_candidatos).sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let abaixo: f64 = distribuicao.iter()
+        .filter(|f| f.acertos < acertos)
+        .map(|f| f.percentual_candidatos)
+        .sum();
+    let empatados: f64 = distribuicao.iter()
+        .filter(|f| f.acertos == acertos)
+        .map(|f| f.percentual_candidatos)
+        .sum();
+
+    Some((abaixo + empatados / 2.0) / total * 100.0)
+}
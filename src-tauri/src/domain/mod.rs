@@ -1,4 +1,15 @@
 pub mod simulado;
 pub mod estado;
 pub mod prova;
-pub mod questao;
\ No newline at end of file
+pub mod questao;
+pub mod edicao;
+pub mod tri;
+pub mod regras;
+pub mod resultado;
+pub mod comparacao;
+pub mod estatisticas;
+pub mod caderno;
+pub mod repeticao;
+pub mod sisu;
+pub mod versao_estado;
+pub mod perfil;
\ No newline at end of file
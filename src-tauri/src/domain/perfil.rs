@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use rusqlite::{Row, Result as RusqliteResult};
+
+/// Perfil local de quem usa o app (tabela `usuario`). Simulados, edições, anotações,
+/// caderno de erros e revisões pertencem a um perfil; o app mostra só os do perfil ativo.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Perfil {
+    pub id: i64,
+    pub nome: String,
+    /// Pede PIN para entrar, renomear ou excluir
+    pub tem_pin: bool,
+    pub criado_em: Option<DateTime<Utc>>,
+}
+
+impl Perfil {
+    pub fn from_row(row: &Row<'_>) -> RusqliteResult<Self> {
+        Ok(Perfil {
+            id: row.get("id")?,
+            nome: row.get::<_, Option<String>>("nome")?.unwrap_or_default(),
+            tem_pin: row.get::<_, Option<String>>("pin_hash")?.is_some(),
+            criado_em: row.get("created_at")?,
+        })
+    }
+}
+
+/// PIN de 4 a 8 dígitos
+pub fn validar_pin(pin: &str) -> Result<(), String> {
+    if !(4..=8).contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+        return Err("O PIN deve ter de 4 a 8 dígitos".to_string());
+    }
+    Ok(())
+}
+
+/// Nome sem espaços nas pontas, não vazio e com até 40 caracteres
+pub fn validar_nome(nome: &str) -> Result<String, String> {
+    let nome = nome.trim();
+    if nome.is_empty() {
+        return Err("Informe um nome para o perfil".to_string());
+    }
+    if nome.chars().count() > 40 {
+        return Err("O nome do perfil deve ter até 40 caracteres".to_string());
+    }
+    Ok(nome.to_string())
+}
+
+/// Iterações do PBKDF2-HMAC-SHA256. Com PINs de até 8 dígitos, mais iterações pouco
+/// acrescentam e deixariam a entrada lenta em builds sem otimização. Ficam gravadas
+/// em cada hash, então mudá-las não invalida os PINs já definidos.
+const ITERACOES_PIN: u32 = 100_000;
+const ALGORITMO_PIN: &str = "pbkdf2-sha256";
+const TAMANHO_SAL: usize = 16;
+const TAMANHO_HASH: usize = 32;
+
+/// Gera o `pin_hash` gravado no banco: `pbkdf2-sha256$iterações$sal$hash`,
+/// com sal aleatório do sistema e sal e hash em hexadecimal.
+///
+/// O PIN só separa os perfis de quem divide o computador: quem tem acesso ao
+/// arquivo do banco lê tudo de qualquer forma. O KDF evita que o PIN em si seja
+/// recuperado do banco com facilidade.
+pub fn gerar_hash_pin(pin: &str) -> Result<String, String> {
+    let mut sal = [0u8; TAMANHO_SAL];
+    getrandom::getrandom(&mut sal)
+        .map_err(|e| format!("Erro ao gerar o sal do PIN: {}", e))?;
+    let hash = hash_pin(pin, &sal, ITERACOES_PIN);
+    Ok(format!("{}${}${}${}", ALGORITMO_PIN, ITERACOES_PIN, para_hex(&sal), para_hex(&hash)))
+}
+
+pub fn conferir_pin(pin_hash: &str, pin: &str) -> bool {
+    let partes: Vec<&str> = pin_hash.split('$').collect();
+    let [algoritmo, iteracoes, sal, hash] = partes[..] else {
+        return false;
+    };
+    if algoritmo != ALGORITMO_PIN {
+        return false;
+    }
+    let (Ok(iteracoes), Some(sal), Some(hash)) = (iteracoes.parse::<u32>(), de_hex(sal), de_hex(hash)) else {
+        return false;
+    };
+    if iteracoes == 0 || hash.len() != TAMANHO_HASH {
+        return false;
+    }
+    
+    // Compara todos os bytes, sem sair no primeiro diferente
+    let calculado = hash_pin(pin, &sal, iteracoes);
+    calculado.iter().zip(&hash).fold(0u8, |diferenca, (a, b)| diferenca | (a ^ b)) == 0
+}
+
+fn hash_pin(pin: &str, sal: &[u8], iteracoes: u32) -> [u8; TAMANHO_HASH] {
+    let mut hash = [0u8; TAMANHO_HASH];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(pin.as_bytes(), sal, iteracoes, &mut hash);
+    hash
+}
+
+fn para_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn de_hex(texto: &str) -> Option<Vec<u8>> {
+    if texto.len() % 2 != 0 || !texto.is_ascii() {
+        return None;
+    }
+    (0..texto.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&texto[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pin_confere_so_com_o_mesmo_valor() {
+        let pin_hash = gerar_hash_pin("1234").unwrap();
+        assert!(pin_hash.starts_with("pbkdf2-sha256$100000$"));
+        assert!(conferir_pin(&pin_hash, "1234"));
+        assert!(!conferir_pin(&pin_hash, "1235"));
+        assert!(!conferir_pin(&pin_hash, ""));
+    }
+
+    #[test]
+    fn cada_hash_tem_seu_sal() {
+        assert_ne!(gerar_hash_pin("1234").unwrap(), gerar_hash_pin("1234").unwrap());
+    }
+
+    #[test]
+    fn hash_malformado_nao_confere() {
+        let sal = para_hex(&[7; TAMANHO_SAL]);
+        let hash = para_hex(&hash_pin("1234", &[7; TAMANHO_SAL], 1));
+        assert!(conferir_pin(&format!("pbkdf2-sha256$1${}${}", sal, hash), "1234"));
+        
+        for invalido in [
+            "",
+            "0123456789abcdef:0123456789abcdef",
+            &format!("md5$1${}${}", sal, hash),
+            &format!("pbkdf2-sha256$0${}${}", sal, hash),
+            &format!("pbkdf2-sha256$1${}${}", sal, &hash[..10]),
+            &format!("pbkdf2-sha256$1$xyz${}", hash),
+            &format!("pbkdf2-sha256$1${}${}$extra", sal, hash),
+        ] {
+            assert!(!conferir_pin(invalido, "1234"), "{}", invalido);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use crate::domain::questao::Questao;
+use crate::domain::regras::RegrasPontuacao;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Prova {
@@ -11,6 +12,8 @@ pub struct Prova {
     pub duracao_minutos: u16,
     pub total_questoes: usize,
     pub questoes: Vec<Questao>,
+    #[serde(default)]
+    pub regras: Option<RegrasPontuacao>,
 }
 
 impl Prova {
@@ -44,6 +47,13 @@ impl Prova {
                     id: questao.id.clone(),
                 });
             }
+            
+            if let Some(tri) = &questao.tri {
+                tri.validar().map_err(|motivo| ProvaError::InvalidIrtParameters {
+                    id: questao.id.clone(),
+                    motivo,
+                })?;
+            }
         }
 
         Ok(())
@@ -88,4 +98,6 @@ pub enum ProvaError {
     DuplicateQuestionId(String),
     #[error("Número da questão {id} inconsistente: esperado {expected}, encontrado {actual}")]
     QuestionNumberMismatch { expected: u32, actual: u32, id: String },
+    #[error("Parâmetros TRI inválidos na questão {id}: {motivo}")]
+    InvalidIrtParameters { id: String, motivo: String },
 }
\ No newline at end of file
@@ -10,6 +10,34 @@ pub struct Questao {
     pub imagens: Vec<String>, // caminhos relativos a assets/
     pub alternativas: Vec<Alternativa>,
     pub resposta_correta: String, // ex: "C"
+    #[serde(default)]
+    pub explicacao: Option<String>, // resolução exibida no modo estudo
+    #[serde(default)]
+    pub tri: Option<ParametrosTri>, // parâmetros do item no modelo logístico de 3 parâmetros
+}
+
+/// Parâmetros do item na TRI (modelo 3PL), na métrica N(0, 1) da proficiência
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ParametrosTri {
+    pub a: f64, // discriminação
+    pub b: f64, // dificuldade
+    pub c: f64, // acerto ao acaso
+}
+
+impl ParametrosTri {
+    /// `a` positivo, `b` finito e `c` em [0, 1)
+    pub fn validar(&self) -> Result<(), String> {
+        if !(self.a > 0.0 && self.a.is_finite()) {
+            return Err(format!("discriminação `a` deve ser positiva (recebido {})", self.a));
+        }
+        if !self.b.is_finite() {
+            return Err(format!("dificuldade `b` inválida ({})", self.b));
+        }
+        if !(0.0..1.0).contains(&self.c) {
+            return Err(format!("acerto ao acaso `c` deve estar em [0, 1) (recebido {})", self.c));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
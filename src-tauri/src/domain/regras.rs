@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Regras de pontuação de um vestibular. Podem vir no `prova.json` (campo `regras`)
+/// ou em `provas/{vestibular}/regras.json`, valendo para todas as provas do vestibular;
+/// as da prova têm precedência.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RegrasPontuacao {
+    /// Fração do valor da questão descontada a cada erro (ex: 0.25). Brancos não são penalizados.
+    #[serde(default)]
+    pub penalidade_erro: f64,
+    /// Peso por área (`area_id` => peso); áreas ausentes valem 1
+    #[serde(default)]
+    pub pesos_area: HashMap<String, f64>,
+    /// Peso por questão (`Q01` => peso); questões ausentes valem 1
+    #[serde(default)]
+    pub pesos_questao: HashMap<String, f64>,
+    /// Percentual mínimo de acertos por área; abaixo dele o candidato é eliminado
+    #[serde(default)]
+    pub nota_minima_area: HashMap<String, f64>,
+}
+
+/// Situação de uma questão para o cálculo da pontuação
+pub struct ItemPontuado<'a> {
+    pub questao_id: &'a str,
+    pub area_id: &'a str,
+    pub acertou: bool,
+    pub em_branco: bool,
+}
+
+/// Explicação de um ajuste feito pelas regras; `valor` é o efeito em pontos percentuais
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AjusteRegra {
+    pub descricao: String,
+    pub valor: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AplicacaoRegras {
+    pub pontuacao_sem_regras: f64,
+    pub pontos_obtidos: f64,
+    pub pontos_possiveis: f64,
+    pub pontuacao: f64,
+    pub eliminado: bool,
+    pub ajustes: Vec<AjusteRegra>,
+}
+
+impl RegrasPontuacao {
+    /// Penalidade e pesos não podem ser negativos: inverteriam o sentido da pontuação
+    pub fn validar(&self) -> Result<(), String> {
+        if !(self.penalidade_erro >= 0.0 && self.penalidade_erro.is_finite()) {
+            return Err(format!("penalidade_erro deve ser um número não negativo (recebido {})", self.penalidade_erro));
+        }
+        for (campo, pesos) in [("pesos_area", &self.pesos_area), ("pesos_questao", &self.pesos_questao)] {
+            let mut invalidos: Vec<&String> = pesos.iter()
+                .filter(|(_, peso)| !(**peso >= 0.0 && peso.is_finite()))
+                .map(|(id, _)| id)
+                .collect();
+            if !invalidos.is_empty() {
+                invalidos.sort();
+                return Err(format!("{} tem peso negativo ou inválido em {:?}", campo, invalidos));
+            }
+        }
+        Ok(())
+    }
+
+    fn valor_questao(&self, item: &ItemPontuado) -> f64 {
+        let peso_questao = self.pesos_questao.get(item.questao_id).copied().unwrap_or(1.0);
+        let peso_area = self.pesos_area.get(item.area_id).copied().unwrap_or(1.0);
+        peso_questao * peso_area
+    }
+
+    pub fn aplicar(&self, itens: &[ItemPontuado]) -> AplicacaoRegras {
+        let total = itens.len();
+        let acertos = itens.iter().filter(|i| i.acertou).count();
+        let pontuacao_sem_regras = percentual(acertos as f64, total as f64);
+        let mut ajustes = Vec::new();
+
+        let pontos_possiveis: f64 = itens.iter().map(|i| self.valor_questao(i)).sum();
+        let pontos_acertos: f64 = itens.iter()
+            .filter(|i| i.acertou)
+            .map(|i| self.valor_questao(i))
+            .sum();
+
+        let pontuacao_ponderada = percentual(pontos_acertos, pontos_possiveis);
+        if (pontuacao_ponderada - pontuacao_sem_regras).abs() > f64::EPSILON {
+            ajustes.push(AjusteRegra {
+                descricao: format!("Pesos aplicados: {:.2} de {:.2} pontos possíveis", pontos_acertos, pontos_possiveis),
+                valor: pontuacao_ponderada - pontuacao_sem_regras,
+            });
+        }
+
+        let mut pontos_obtidos = pontos_acertos;
+        if self.penalidade_erro > 0.0 {
+            let errados: Vec<&ItemPontuado> = itens.iter().filter(|i| !i.acertou && !i.em_branco).collect();
+            let desconto: f64 = errados.iter().map(|i| self.valor_questao(i) * self.penalidade_erro).sum();
+            if desconto > 0.0 {
+                pontos_obtidos -= desconto;
+                ajustes.push(AjusteRegra {
+                    descricao: format!("{} erro(s) com penalidade de {} do valor da questão: -{:.2} pontos",
+                        errados.len(), self.penalidade_erro, desconto),
+                    valor: -percentual(desconto, pontos_possiveis),
+                });
+            }
+        }
+
+        if pontos_obtidos < 0.0 {
+            ajustes.push(AjusteRegra {
+                descricao: "Pontuação negativa limitada a zero".to_string(),
+                valor: percentual(-pontos_obtidos, pontos_possiveis),
+            });
+            pontos_obtidos = 0.0;
+        }
+
+        let mut eliminado = false;
+        let mut minimas: Vec<(&String, &f64)> = self.nota_minima_area.iter().collect();
+        minimas.sort_by(|a, b| a.0.cmp(b.0));
+        for (area_id, minimo) in minimas {
+            let da_area: Vec<&ItemPontuado> = itens.iter().filter(|i| i.area_id == area_id.as_str()).collect();
+            if da_area.is_empty() {
+                continue;
+            }
+            let acertos_area = da_area.iter().filter(|i| i.acertou).count();
+            let percentual_area = percentual(acertos_area as f64, da_area.len() as f64);
+            if percentual_area < *minimo {
+                eliminado = true;
+                ajustes.push(AjusteRegra {
+                    descricao: format!("Área {}: {:.1}% abaixo do mínimo de {:.1}% — candidato eliminado",
+                        area_id, percentual_area, minimo),
+                    valor: 0.0,
+                });
+            }
+        }
+
+        AplicacaoRegras {
+            pontuacao_sem_regras,
+            pontos_obtidos,
+            pontos_possiveis,
+            pontuacao: percentual(pontos_obtidos, pontos_possiveis),
+            eliminado,
+            ajustes,
+        }
+    }
+}
+
+fn percentual(parte: f64, total: f64) -> f64 {
+    if total > 0.0 { parte / total * 100.0 } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item<'a>(questao_id: &'a str, area_id: &'a str, acertou: bool, em_branco: bool) -> ItemPontuado<'a> {
+        ItemPontuado { questao_id, area_id, acertou, em_branco }
+    }
+
+    /// Q1 e Q2 certas, Q3 errada, Q4 em branco
+    fn itens() -> Vec<ItemPontuado<'static>> {
+        vec![
+            item("Q1", "MT", true, false),
+            item("Q2", "MT", true, false),
+            item("Q3", "LC", false, false),
+            item("Q4", "LC", false, true),
+        ]
+    }
+
+    #[test]
+    fn sem_regras_e_o_percentual_de_acertos() {
+        let aplicacao = RegrasPontuacao::default().aplicar(&itens());
+        assert_eq!(aplicacao.pontuacao_sem_regras, 50.0);
+        assert_eq!(aplicacao.pontuacao, 50.0);
+        assert!(aplicacao.ajustes.is_empty());
+        assert!(!aplicacao.eliminado);
+    }
+
+    #[test]
+    fn penalidade_desconta_so_os_erros() {
+        let regras = RegrasPontuacao { penalidade_erro: 0.25, ..Default::default() };
+        let aplicacao = regras.aplicar(&itens());
+        
+        // 2 acertos - 0.25 pela Q3; a Q4 em branco não é penalizada
+        assert!((aplicacao.pontos_obtidos - 1.75).abs() < 1e-9);
+        assert!((aplicacao.pontuacao - 43.75).abs() < 1e-9);
+        assert_eq!(aplicacao.ajustes.len(), 1);
+    }
+
+    #[test]
+    fn penalidade_nao_deixa_a_pontuacao_negativa() {
+        let regras = RegrasPontuacao { penalidade_erro: 1.0, ..Default::default() };
+        let erros = vec![item("Q1", "MT", false, false), item("Q2", "MT", false, false)];
+        let aplicacao = regras.aplicar(&erros);
+        
+        assert_eq!(aplicacao.pontos_obtidos, 0.0);
+        assert_eq!(aplicacao.pontuacao, 0.0);
+        assert!(aplicacao.ajustes.iter().any(|a| a.descricao.contains("limitada a zero")));
+    }
+
+    #[test]
+    fn pesos_de_area_e_questao_se_multiplicam() {
+        let regras = RegrasPontuacao {
+            pesos_area: HashMap::from([("MT".to_string(), 2.0)]),
+            pesos_questao: HashMap::from([("Q1".to_string(), 3.0)]),
+            ..Default::default()
+        };
+        let aplicacao = regras.aplicar(&itens());
+        
+        // Q1 = 3 × 2, Q2 = 2, Q3 = Q4 = 1
+        assert_eq!(aplicacao.pontos_possiveis, 10.0);
+        assert_eq!(aplicacao.pontos_obtidos, 8.0);
+        assert_eq!(aplicacao.pontuacao, 80.0);
+        assert!((aplicacao.ajustes[0].valor - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn nota_minima_elimina() {
+        let regras = RegrasPontuacao {
+            nota_minima_area: HashMap::from([("LC".to_string(), 30.0), ("MT".to_string(), 30.0)]),
+            ..Default::default()
+        };
+        let aplicacao = regras.aplicar(&itens());
+        assert!(aplicacao.eliminado);
+        assert_eq!(aplicacao.ajustes.len(), 1);
+        assert!(aplicacao.ajustes[0].descricao.contains("LC"));
+    }
+
+    #[test]
+    fn valores_negativos_sao_recusados() {
+        assert!(RegrasPontuacao::default().validar().is_ok());
+        assert!(RegrasPontuacao { penalidade_erro: -0.25, ..Default::default() }.validar().is_err());
+        assert!(RegrasPontuacao { penalidade_erro: f64::NAN, ..Default::default() }.validar().is_err());
+        assert!(RegrasPontuacao {
+            pesos_area: HashMap::from([("MT".to_string(), -1.0)]),
+            ..Default::default()
+        }.validar().is_err());
+        assert!(RegrasPontuacao {
+            pesos_questao: HashMap::from([("Q1".to_string(), -2.0)]),
+            ..Default::default()
+        }.validar().is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use rusqlite::{Row, Result as RusqliteResult};
+
+/// Facilidade inicial e mínima do SM-2
+pub const FACILIDADE_INICIAL: f64 = 2.5;
+const FACILIDADE_MINIMA: f64 = 1.3;
+/// Maior intervalo entre revisões (100 anos). Sem teto, o intervalo cresce
+/// geometricamente e sai do intervalo de `NaiveDate` depois de umas 20 revisões certas.
+pub const INTERVALO_MAXIMO_DIAS: u32 = 36_500;
+
+/// Nota usada quando a questão é feita dentro de um simulado, sem autoavaliação
+pub const NOTA_ACERTO_SIMULADO: u8 = 4;
+pub const NOTA_ERRO_SIMULADO: u8 = 1;
+
+/// Cartão de revisão espaçada de uma questão da biblioteca (algoritmo SM-2)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CartaoRevisao {
+    pub id: i64,
+    /// Perfil dono do cartão
+    pub usuario_id: i64,
+    pub prova_id: String,
+    pub questao_id: String,
+    pub area_id: String,
+    /// Revisões seguidas com nota >= 3
+    pub repeticoes: u32,
+    pub intervalo_dias: u32,
+    pub facilidade: f64,
+    pub proxima_revisao: NaiveDate,
+    pub ultima_revisao: Option<DateTime<Utc>>,
+    pub ultima_nota: Option<u8>,
+}
+
+impl CartaoRevisao {
+    pub fn from_row(row: &Row<'_>) -> RusqliteResult<Self> {
+        Ok(CartaoRevisao {
+            id: row.get("id")?,
+            usuario_id: row.get("usuario_id")?,
+            prova_id: row.get("prova_id")?,
+            questao_id: row.get("questao_id")?,
+            area_id: row.get("area_id")?,
+            repeticoes: row.get("repeticoes")?,
+            intervalo_dias: row.get("intervalo_dias")?,
+            facilidade: row.get("facilidade")?,
+            proxima_revisao: row.get("proxima_revisao")?,
+            ultima_revisao: row.get("ultima_revisao")?,
+            ultima_nota: row.get("ultima_nota")?,
+        })
+    }
+
+    /// Aplica uma revisão com nota de 0 (não lembrou nada) a 5 (resposta perfeita)
+    pub fn revisar(&mut self, nota: u8, hoje: NaiveDate) {
+        let nota = nota.min(5);
+        if nota >= 3 {
+            self.intervalo_dias = match self.repeticoes {
+                0 => 1,
+                1 => 6,
+                _ => ((self.intervalo_dias as f64 * self.facilidade).round() as u32).min(INTERVALO_MAXIMO_DIAS),
+            };
+            self.repeticoes += 1;
+        } else {
+            self.repeticoes = 0;
+            self.intervalo_dias = 1;
+        }
+
+        let distancia = (5 - nota) as f64;
+        self.facilidade = (self.facilidade + 0.1 - distancia * (0.08 + distancia * 0.02)).max(FACILIDADE_MINIMA);
+        self.proxima_revisao = hoje
+            .checked_add_days(Days::new(self.intervalo_dias as u64))
+            .unwrap_or(NaiveDate::MAX);
+        self.ultima_revisao = Some(Utc::now());
+        self.ultima_nota = Some(nota);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cartao_novo(hoje: NaiveDate) -> CartaoRevisao {
+        CartaoRevisao {
+            id: 1,
+            usuario_id: 1,
+            prova_id: "enem/2023-dia1".to_string(),
+            questao_id: "Q01".to_string(),
+            area_id: "LC".to_string(),
+            repeticoes: 0,
+            intervalo_dias: 0,
+            facilidade: FACILIDADE_INICIAL,
+            proxima_revisao: hoje,
+            ultima_revisao: None,
+            ultima_nota: None,
+        }
+    }
+
+    fn hoje() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn intervalos_seguem_o_sm2() {
+        let mut cartao = cartao_novo(hoje());
+        
+        cartao.revisar(5, hoje());
+        assert_eq!(cartao.intervalo_dias, 1);
+        cartao.revisar(5, hoje());
+        assert_eq!(cartao.intervalo_dias, 6);
+        
+        // A partir da terceira, o intervalo anterior vezes a facilidade
+        let facilidade = cartao.facilidade;
+        cartao.revisar(5, hoje());
+        assert_eq!(cartao.intervalo_dias, (6.0 * facilidade).round() as u32);
+        assert_eq!(cartao.repeticoes, 3);
+        assert_eq!(cartao.proxima_revisao, hoje() + Days::new(cartao.intervalo_dias as u64));
+    }
+
+    #[test]
+    fn nota_cinco_aumenta_e_nota_quatro_mantem_a_facilidade() {
+        let mut cartao = cartao_novo(hoje());
+        cartao.revisar(5, hoje());
+        assert!((cartao.facilidade - (FACILIDADE_INICIAL + 0.1)).abs() < 1e-9);
+        
+        let mut cartao = cartao_novo(hoje());
+        cartao.revisar(4, hoje());
+        assert!((cartao.facilidade - FACILIDADE_INICIAL).abs() < 1e-9);
+    }
+
+    #[test]
+    fn erro_reinicia_as_repeticoes() {
+        let mut cartao = cartao_novo(hoje());
+        cartao.revisar(5, hoje());
+        cartao.revisar(5, hoje());
+        cartao.revisar(NOTA_ERRO_SIMULADO, hoje());
+        
+        assert_eq!(cartao.repeticoes, 0);
+        assert_eq!(cartao.intervalo_dias, 1);
+        assert_eq!(cartao.ultima_nota, Some(NOTA_ERRO_SIMULADO));
+    }
+
+    #[test]
+    fn facilidade_nao_cai_abaixo_do_minimo() {
+        let mut cartao = cartao_novo(hoje());
+        for _ in 0..20 {
+            cartao.revisar(0, hoje());
+        }
+        assert_eq!(cartao.facilidade, FACILIDADE_MINIMA);
+    }
+
+    #[test]
+    fn intervalo_para_no_teto() {
+        let mut cartao = cartao_novo(hoje());
+        for _ in 0..100 {
+            cartao.revisar(5, hoje());
+        }
+        assert_eq!(cartao.intervalo_dias, INTERVALO_MAXIMO_DIAS);
+        assert_eq!(cartao.proxima_revisao, hoje() + Days::new(INTERVALO_MAXIMO_DIAS as u64));
+        
+        // Cartão gravado antes do teto, já perto do fim do calendário
+        cartao.intervalo_dias = u32::MAX;
+        cartao.revisar(5, NaiveDate::MAX - Days::new(10));
+        assert_eq!(cartao.intervalo_dias, INTERVALO_MAXIMO_DIAS);
+        assert_eq!(cartao.proxima_revisao, NaiveDate::MAX);
+    }
+
+    #[test]
+    fn nota_acima_de_cinco_conta_como_cinco() {
+        let mut cartao = cartao_novo(hoje());
+        cartao.revisar(9, hoje());
+        assert_eq!(cartao.ultima_nota, Some(5));
+        assert!((cartao.facilidade - (FACILIDADE_INICIAL + 0.1)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use crate::domain::estado::ModoSimulado;
+use crate::domain::estatisticas::Comparativo;
+use crate::domain::regras::AplicacaoRegras;
+use crate::domain::tri::EstimativaTri;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetalheQuestao {
+    pub questao_id: String,
+    pub numero: u32,
+    pub area_id: String,
+    pub resposta_usuario: Option<String>,
+    pub gabarito: String,
+    pub acertou: bool,
+    pub eliminadas: Vec<String>,
+    pub eliminou_correta: bool,
+    pub tempo_segundos: Option<u32>,
+    /// Percentual de acerto dos candidatos na edição oficial, se a prova traz estatísticas
+    #[serde(default)]
+    pub taxa_acerto_nacional: Option<f64>,
+}
+
+/// Desempenho agregado de uma área da prova
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResultadoArea {
+    pub area_id: String,
+    pub total: usize,
+    pub acertos: usize,
+    pub erros: usize,
+    pub brancos: usize,
+    pub percentual: f64,
+    /// Soma do tempo gasto nas questões da área; `None` se o tempo por questão não foi registrado
+    pub tempo_segundos: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResultadoSimulado {
+    pub simulado_id: i64,
+    pub modo: ModoSimulado,
+    pub total_questoes: usize,
+    pub acertos: usize,
+    pub erros: usize, // respondidas com a alternativa errada
+    pub brancos: usize,
+    pub pontuacao: f64,
+    pub areas: Vec<ResultadoArea>,
+    pub detalhes: Vec<DetalheQuestao>, 
+    /// Nota estimada pela TRI por área, quando a prova traz os parâmetros dos itens
+    pub tri: Vec<EstimativaTri>,
+    /// Penalidades, pesos e notas mínimas aplicados; quando presente, `pontuacao` já é a ajustada
+    pub regras: Option<AplicacaoRegras>,
+    /// Percentil frente à distribuição oficial, quando a prova traz `estatisticas.json`
+    #[serde(default)]
+    pub comparativo: Option<Comparativo>,
+}
+
+pub fn percentual(acertos: usize, total: usize) -> f64 {
+    if total > 0 { (acertos as f64 / total as f64) * 100.0 } else { 0.0 }
+}
+
+/// Histórico de uma questão em todos os simulados da prova (tabela `resposta`)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EstatisticaQuestao {
+    pub prova_id: String,
+    pub questao_id: String,
+    /// Vezes em que a questão foi respondida (brancos não contam)
+    pub respondida: u32,
+    pub acertos: u32,
+    /// Alternativas marcadas, da mais escolhida para a menos
+    pub alternativas: Vec<AlternativaMarcada>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlternativaMarcada {
+    pub alternativa: String,
+    pub vezes: u32,
+}
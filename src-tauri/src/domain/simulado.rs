@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use rusqlite::{Row, Result as RusqliteResult};
 use crate::domain::estado::EstadoSimuladoCompleto;
+use crate::domain::versao_estado::{self, ErroEstado};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Simulado {
@@ -15,6 +16,9 @@ pub struct Simulado {
     #[serde(with = "chrono::serde::ts_seconds_option")]
     pub finalizado_em: Option<DateTime<Utc>>,
     pub estado_json: String,
+    /// Preenchido quando o simulado vai para a lixeira
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub excluido_em: Option<DateTime<Utc>>,
 }
 
 impl Simulado {
@@ -46,12 +50,13 @@ impl Simulado {
             iniciado_em: None,
             finalizado_em: None,
             estado_json: serde_json::to_string(&estado)?,
+            excluido_em: None,
         })
     }
 
-    // ✅ Acesso ao estado
-    pub fn estado(&self) -> Result<EstadoSimuladoCompleto, serde_json::Error> {
-        serde_json::from_str(&self.estado_json)
+    // ✅ Acesso ao estado (formatos antigos são atualizados na leitura)
+    pub fn estado(&self) -> Result<EstadoSimuladoCompleto, ErroEstado> {
+        versao_estado::ler(&self.estado_json)
     }
 
     // ✅ Atualização do estado
@@ -71,6 +76,7 @@ impl Simulado {
             iniciado_em: row.get("iniciado_em")?,
             finalizado_em: row.get("finalizado_em")?,
             estado_json: row.get("estado_json")?,
+            excluido_em: row.get("excluido_em")?,
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Áreas do ENEM usadas pelo SISU
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AreaEnem {
+    Linguagens,
+    Humanas,
+    Natureza,
+    Matematica,
+    Redacao,
+}
+
+impl AreaEnem {
+    pub const TODAS: [AreaEnem; 5] = [
+        AreaEnem::Linguagens,
+        AreaEnem::Humanas,
+        AreaEnem::Natureza,
+        AreaEnem::Matematica,
+        AreaEnem::Redacao,
+    ];
+
+    /// Reconhece a área pelo nome usado nas provas ou nos arquivos de notas
+    /// (ex: "LINGUAGENS, CÓDIGOS E SUAS TECNOLOGIAS", "matematica", "Ciências da Natureza")
+    pub fn identificar(nome: &str) -> Option<Self> {
+        let nome: String = nome
+            .to_lowercase()
+            .chars()
+            .map(|c| match c {
+                'á' | 'à' | 'â' | 'ã' => 'a',
+                'é' | 'ê' => 'e',
+                'í' => 'i',
+                'ó' | 'ô' | 'õ' => 'o',
+                'ú' => 'u',
+                'ç' => 'c',
+                c => c,
+            })
+            .collect();
+
+        if nome.contains("linguagens") {
+            Some(Self::Linguagens)
+        } else if nome.contains("humanas") {
+            Some(Self::Humanas)
+        } else if nome.contains("natureza") {
+            Some(Self::Natureza)
+        } else if nome.contains("matematica") {
+            Some(Self::Matematica)
+        } else if nome.contains("redacao") {
+            Some(Self::Redacao)
+        } else {
+            None
+        }
+    }
+}
+
+/// Base de cursos importada pelo usuário (ex: `cursos_sisu.json`)
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BaseSisu {
+    #[serde(default)]
+    pub fonte: Option<String>,
+    pub cursos: Vec<CursoSisu>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CursoSisu {
+    pub id: String,
+    pub nome: String,
+    pub instituicao: String,
+    #[serde(default)]
+    pub campus: Option<String>,
+    #[serde(default)]
+    pub turno: Option<String>,
+    /// Peso de cada área na média do curso; áreas ausentes têm peso 1
+    #[serde(default)]
+    pub pesos: HashMap<AreaEnem, f64>,
+    /// Notas de corte de edições anteriores
+    #[serde(default)]
+    pub notas_corte: Vec<NotaCorte>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotaCorte {
+    pub ano: u32,
+    pub nota: f64,
+    /// Modalidade de concorrência (ampla concorrência, cotas...); `None` = ampla
+    #[serde(default)]
+    pub modalidade: Option<String>,
+}
+
+impl CursoSisu {
+    pub fn peso(&self, area: AreaEnem) -> f64 {
+        self.pesos.get(&area).copied().unwrap_or(1.0)
+    }
+
+    /// Média ponderada das notas; `Err` com as áreas que faltam para o cálculo
+    pub fn media(&self, notas: &HashMap<AreaEnem, f64>) -> Result<f64, Vec<AreaEnem>> {
+        let faltando: Vec<AreaEnem> = AreaEnem::TODAS.into_iter()
+            .filter(|area| self.peso(*area) > 0.0 && !notas.contains_key(area))
+            .collect();
+        if !faltando.is_empty() {
+            return Err(faltando);
+        }
+
+        let soma_pesos: f64 = AreaEnem::TODAS.iter().map(|a| self.peso(*a)).sum();
+        if soma_pesos <= 0.0 {
+            return Ok(0.0);
+        }
+        let soma: f64 = AreaEnem::TODAS.iter()
+            .filter_map(|a| notas.get(a).map(|nota| nota * self.peso(*a)))
+            .sum();
+        Ok(soma / soma_pesos)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ComparacaoCorte {
+    pub ano: u32,
+    pub modalidade: Option<String>,
+    pub nota_corte: f64,
+    /// Média do aluno menos a nota de corte
+    pub diferenca: f64,
+    pub passaria: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SimulacaoCurso {
+    pub curso: CursoSisu,
+    pub media: Option<f64>,
+    /// Áreas sem nota que impediram o cálculo
+    pub areas_faltando: Vec<AreaEnem>,
+    /// Da edição mais recente para a mais antiga
+    pub cortes: Vec<ComparacaoCorte>,
+}
+
+impl SimulacaoCurso {
+    pub fn calcular(curso: &CursoSisu, notas: &HashMap<AreaEnem, f64>) -> Self {
+        let (media, areas_faltando) = match curso.media(notas) {
+            Ok(media) => (Some(media), Vec::new()),
+            Err(faltando) => (None, faltando),
+        };
+
+        let mut cortes: Vec<ComparacaoCorte> = media
+            .map(|media| {
+                curso.notas_corte.iter()
+                    .map(|corte| ComparacaoCorte {
+                        ano: corte.ano,
+                        modalidade: corte.modalidade.clone(),
+                        nota_corte: corte.nota,
+                        diferenca: media - corte.nota,
+                        passaria: media >= corte.nota,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        cortes.sort_by(|a, b| b.ano.cmp(&a.ano));
+
+        SimulacaoCurso { curso: curso.clone(), media, areas_faltando, cortes }
+    }
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use crate::domain::questao::ParametrosTri;
+
+/// Constante de escala do modelo logístico (aproxima a ogiva normal)
+const D: f64 = 1.7;
+/// Pontos de quadratura usados na integração da posterior
+const PONTOS_QUADRATURA: usize = 81;
+const THETA_MIN: f64 = -4.0;
+const THETA_MAX: f64 = 4.0;
+/// Transformação da métrica N(0, 1) para a escala do ENEM (média 500, desvio 100)
+const MEDIA_ESCALA: f64 = 500.0;
+const DESVIO_ESCALA: f64 = 100.0;
+/// Quantil da normal para o intervalo de confiança de 95%
+const Z_95: f64 = 1.96;
+
+/// Proficiência estimada por EAP (média a posteriori, priori N(0, 1))
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EstimativaTri {
+    pub area_id: String,
+    pub itens: usize,
+    pub theta: f64,
+    pub erro_padrao: f64,
+    pub nota: f64,
+    pub intervalo_inferior: f64,
+    pub intervalo_superior: f64,
+}
+
+/// Probabilidade de acerto no modelo 3PL
+pub fn probabilidade_acerto(item: &ParametrosTri, theta: f64) -> f64 {
+    item.c + (1.0 - item.c) / (1.0 + (-D * item.a * (theta - item.b)).exp())
+}
+
+/// Estima a proficiência de uma área a partir do padrão de respostas.
+/// Questões em branco entram como erro, como no ENEM.
+pub fn estimar_eap(area_id: &str, respostas: &[(ParametrosTri, bool)]) -> EstimativaTri {
+    let passo = (THETA_MAX - THETA_MIN) / (PONTOS_QUADRATURA - 1) as f64;
+    
+    // Log da posterior (a menos de constante) em cada ponto: em provas longas a
+    // verossimilhança em si fica abaixo do menor f64
+    let pontos: Vec<(f64, f64)> = (0..PONTOS_QUADRATURA)
+        .map(|i| {
+            let theta = THETA_MIN + i as f64 * passo;
+            let log_priori = -0.5 * theta * theta;
+            let log_verossimilhanca: f64 = respostas.iter()
+                .map(|(item, acertou)| {
+                    let p = probabilidade_acerto(item, theta).clamp(1e-10, 1.0 - 1e-10);
+                    if *acertou { p.ln() } else { (1.0 - p).ln() }
+                })
+                .sum();
+            (theta, log_priori + log_verossimilhanca)
+        })
+        .collect();
+    
+    // Log-sum-exp: pesos relativos ao ponto mais provável, que vale 1
+    let maximo = pontos.iter().map(|(_, log_peso)| *log_peso).fold(f64::NEG_INFINITY, f64::max);
+    
+    let mut soma_peso = 0.0;
+    let mut soma_theta = 0.0;
+    let mut soma_theta2 = 0.0;
+    for (theta, log_peso) in pontos {
+        let peso = (log_peso - maximo).exp();
+        soma_peso += peso;
+        soma_theta += peso * theta;
+        soma_theta2 += peso * theta * theta;
+    }
+    
+    let theta = soma_theta / soma_peso;
+    let erro_padrao = (soma_theta2 / soma_peso - theta * theta).max(0.0).sqrt();
+    
+    EstimativaTri {
+        area_id: area_id.to_string(),
+        itens: respostas.len(),
+        theta,
+        erro_padrao,
+        nota: para_escala_enem(theta),
+        intervalo_inferior: para_escala_enem(theta - Z_95 * erro_padrao),
+        intervalo_superior: para_escala_enem(theta + Z_95 * erro_padrao),
+    }
+}
+
+/// Converte a proficiência para a escala 0–1000 do ENEM
+pub fn para_escala_enem(theta: f64) -> f64 {
+    (MEDIA_ESCALA + DESVIO_ESCALA * theta).clamp(0.0, 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Itens com dificuldades igualmente espaçadas em [-2, 2]
+    fn itens(quantidade: usize, a: f64) -> Vec<ParametrosTri> {
+        (0..quantidade)
+            .map(|i| ParametrosTri {
+                a,
+                b: -2.0 + 4.0 * i as f64 / (quantidade - 1) as f64,
+                c: 0.2,
+            })
+            .collect()
+    }
+
+    /// Acerta os `acertos` itens mais fáceis
+    fn padrao(itens: &[ParametrosTri], acertos: usize) -> Vec<(ParametrosTri, bool)> {
+        itens.iter().enumerate().map(|(i, item)| (*item, i < acertos)).collect()
+    }
+
+    #[test]
+    fn tudo_certo_e_tudo_errado_vao_aos_extremos() {
+        let itens = itens(45, 1.5);
+        
+        let tudo_certo = estimar_eap("CN", &padrao(&itens, 45));
+        assert!(tudo_certo.theta > 2.0, "theta = {}", tudo_certo.theta);
+        assert!(tudo_certo.nota > 700.0);
+        
+        let tudo_errado = estimar_eap("CN", &padrao(&itens, 0));
+        assert!(tudo_errado.theta < -2.0, "theta = {}", tudo_errado.theta);
+        assert!(tudo_errado.nota < 300.0);
+    }
+
+    #[test]
+    fn sem_respostas_devolve_a_priori() {
+        let estimativa = estimar_eap("CN", &[]);
+        assert!(estimativa.theta.abs() < 1e-9);
+        assert!((estimativa.erro_padrao - 1.0).abs() < 0.01);
+        assert_eq!(estimativa.itens, 0);
+    }
+
+    #[test]
+    fn theta_cresce_com_o_numero_de_acertos() {
+        let itens = itens(45, 1.5);
+        let thetas: Vec<f64> = (0..=45)
+            .map(|acertos| estimar_eap("MT", &padrao(&itens, acertos)).theta)
+            .collect();
+        
+        for par in thetas.windows(2) {
+            assert!(par[1] > par[0], "thetas não crescentes: {:?}", thetas);
+        }
+    }
+
+    #[test]
+    fn prova_longa_nao_gera_nan() {
+        // Itens muito discriminativos com padrão incoerente: a verossimilhança
+        // fica abaixo do menor f64 em todos os pontos da quadratura
+        let itens = itens(90, 4.0);
+        let incoerente: Vec<(ParametrosTri, bool)> = itens.iter()
+            .enumerate()
+            .map(|(i, item)| (*item, i % 2 == 1))
+            .collect();
+        
+        let estimativa = estimar_eap("LC", &incoerente);
+        assert!(estimativa.theta.is_finite());
+        assert!(estimativa.erro_padrao.is_finite());
+        assert!(estimativa.nota.is_finite());
+        assert!((THETA_MIN..=THETA_MAX).contains(&estimativa.theta));
+    }
+
+    #[test]
+    fn parametros_invalidos_sao_recusados() {
+        let valido = ParametrosTri { a: 1.2, b: 0.5, c: 0.2 };
+        assert!(valido.validar().is_ok());
+        assert!(ParametrosTri { c: 0.0, ..valido }.validar().is_ok());
+        
+        assert!(ParametrosTri { a: 0.0, ..valido }.validar().is_err());
+        assert!(ParametrosTri { a: -1.0, ..valido }.validar().is_err());
+        assert!(ParametrosTri { a: f64::NAN, ..valido }.validar().is_err());
+        assert!(ParametrosTri { b: f64::INFINITY, ..valido }.validar().is_err());
+        assert!(ParametrosTri { c: 1.0, ..valido }.validar().is_err());
+        assert!(ParametrosTri { c: -0.1, ..valido }.validar().is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! Versionamento do `EstadoSimuladoCompleto` gravado em `simulado.estado_json`.
+//!
+//! O JSON é lido como `serde_json::Value` e passa pelos passos de atualização
+//! a partir da sua versão até `VERSAO_ESTADO`; só então vira a struct. Para mudar
+//! o formato, incremente `VERSAO_ESTADO` e acrescente o passo correspondente em
+//! `PASSOS`. Estados sem o campo `versao` são da versão 1.
+
+use serde_json::{Map, Value};
+use crate::domain::estado::EstadoSimuladoCompleto;
+
+pub const VERSAO_ESTADO: u32 = 3;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ErroEstado {
+    #[error("JSON do estado inválido: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Estado na versão {0}, mais nova que a suportada ({VERSAO_ESTADO})")]
+    VersaoMaisNova(u32),
+    #[error("Estado não é um objeto JSON com uma versão válida")]
+    FormatoInvalido,
+}
+
+/// Passo `i` leva da versão `i + 1` para `i + 2`
+const PASSOS: &[fn(&mut Map<String, Value>)] = &[
+    v1_para_v2,
+    v2_para_v3,
+];
+
+/// Lê o estado gravado, atualizando-o em memória se estiver num formato antigo
+pub fn ler(json: &str) -> Result<EstadoSimuladoCompleto, ErroEstado> {
+    let mut valor: Value = serde_json::from_str(json)?;
+    let objeto = valor.as_object_mut().ok_or(ErroEstado::FormatoInvalido)?;
+
+    let versao = match objeto.get("versao") {
+        None => 1,
+        Some(valor) => match valor.as_u64() {
+            Some(0) | None => return Err(ErroEstado::FormatoInvalido),
+            Some(versao) => u32::try_from(versao).unwrap_or(u32::MAX),
+        },
+    };
+    if versao > VERSAO_ESTADO {
+        return Err(ErroEstado::VersaoMaisNova(versao));
+    }
+    for passo in &PASSOS[(versao - 1) as usize..] {
+        passo(objeto);
+    }
+    objeto.insert("versao".to_string(), VERSAO_ESTADO.into());
+
+    Ok(serde_json::from_value(valor)?)
+}
+
+/// v1 → v2: no modelo antigo `tempo.inicio` era deslocado a cada retomada e não
+/// havia intervalos acumulados nem tempo por questão.
+fn v1_para_v2(estado: &mut Map<String, Value>) {
+    let situacao = estado.get("estado").and_then(Value::as_str).unwrap_or_default().to_string();
+    let sem_tempo_questoes = estado.get("tempo_questoes")
+        .and_then(Value::as_object)
+        .is_none_or(|t| t.is_empty());
+    let Some(tempo) = estado.get_mut("tempo").and_then(Value::as_object_mut) else {
+        return;
+    };
+    let decorrido = tempo.get("decorrido_segundos").and_then(Value::as_u64).unwrap_or(0);
+
+    match situacao.as_str() {
+        "EM_ANDAMENTO" if tempo.get("inicio_intervalo").is_none_or(Value::is_null) => {
+            let inicio = tempo.get("inicio").cloned().unwrap_or(Value::Null);
+            tempo.insert("acumulado_segundos".to_string(), 0.into());
+            tempo.insert("inicio_intervalo".to_string(), inicio);
+        }
+        "EM_ANDAMENTO" | "NAO_INICIADO" => return,
+        _ => {
+            tempo.insert("acumulado_segundos".to_string(), decorrido.into());
+            tempo.insert("inicio_intervalo".to_string(), Value::Null);
+        }
+    }
+
+    // Sem tempo por questão registrado: não atribui o passado à questão atual
+    let questao_desde = tempo.get("questao_desde_segundos").and_then(Value::as_u64).unwrap_or(0);
+    if sem_tempo_questoes && questao_desde == 0 {
+        tempo.insert("questao_desde_segundos".to_string(), decorrido.into());
+    }
+}
+
+/// v2 → v3: a revisão deixou de ser gravada como estado. `EM_REVISAO` não guardava
+/// como o simulado terminou; conta como por tempo quando o limite foi atingido.
+fn v2_para_v3(estado: &mut Map<String, Value>) {
+    if estado.get("estado").and_then(Value::as_str) != Some("EM_REVISAO") {
+        return;
+    }
+    let tempo = estado.get("tempo").and_then(Value::as_object);
+    let limite = tempo.and_then(|t| t.get("limite_minutos")).and_then(Value::as_u64).unwrap_or(0);
+    let decorrido = tempo.and_then(|t| t.get("decorrido_segundos")).and_then(Value::as_u64).unwrap_or(0);
+    let situacao = if limite > 0 && decorrido >= limite * 60 { "FINALIZADO_POR_TEMPO" } else { "FINALIZADO" };
+    estado.insert("estado".to_string(), situacao.into());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::estado::EstadoSimulado;
+
+    /// Estado v1 (sem `versao`), com 10 minutos de prova antes da gravação
+    fn v1(situacao: &str, finalizado_em: &str) -> String {
+        format!(
+            r#"{{"estado":"{}","modo_tempo":"CRONOMETRADO","tempo":{{"limite_minutos":300,"decorrido_segundos":600,"inicio":1704103200,"pausado_em":null,"finalizado_em":{}}},"progresso":{{"questao_atual":"Q03","respondidas":2,"total":45}},"respostas":{{"Q01":"A","Q02":"C"}},"configuracoes":{{"permitir_ultrapassar_tempo":false,"mostrar_gabarito_ao_final":true}}}}"#,
+            situacao, finalizado_em
+        )
+    }
+
+    fn com_versao(versao: Value) -> String {
+        let mut valor: Value = serde_json::from_str(&v1("PAUSADO", "null")).unwrap();
+        valor["versao"] = versao;
+        valor.to_string()
+    }
+
+    #[test]
+    fn v1_em_andamento_abre_o_intervalo_no_inicio() {
+        let estado = ler(&v1("EM_ANDAMENTO", "null")).unwrap();
+        
+        assert_eq!(estado.versao, VERSAO_ESTADO);
+        assert_eq!(estado.estado, EstadoSimulado::EmAndamento);
+        assert_eq!(estado.tempo.acumulado_segundos, 0);
+        assert_eq!(estado.tempo.inicio_intervalo, estado.tempo.inicio);
+        assert_eq!(estado.tempo.questao_desde_segundos, 600);
+        assert_eq!(estado.respostas.len(), 2);
+    }
+
+    #[test]
+    fn v1_finalizado_acumula_o_decorrido() {
+        let estado = ler(&v1("FINALIZADO", "1704103800")).unwrap();
+        
+        assert_eq!(estado.estado, EstadoSimulado::Finalizado);
+        assert_eq!(estado.tempo.acumulado_segundos, 600);
+        assert!(estado.tempo.inicio_intervalo.is_none());
+        assert!(estado.tempo.finalizado_em.is_some());
+    }
+
+    #[test]
+    fn versao_atual_e_lida_sem_alteracao() {
+        let original = ler(&v1("PAUSADO", "null")).unwrap();
+        let json = serde_json::to_value(&original).unwrap();
+        
+        let relido = ler(&json.to_string()).unwrap();
+        assert_eq!(relido.versao, VERSAO_ESTADO);
+        assert_eq!(serde_json::to_value(&relido).unwrap(), json);
+    }
+
+    #[test]
+    fn versao_mais_nova_e_recusada() {
+        let erro = ler(&com_versao((VERSAO_ESTADO + 1).into())).unwrap_err();
+        assert!(matches!(erro, ErroEstado::VersaoMaisNova(v) if v == VERSAO_ESTADO + 1));
+        
+        let erro = ler(&com_versao((u32::MAX as u64 + 2).into())).unwrap_err();
+        assert!(matches!(erro, ErroEstado::VersaoMaisNova(_)));
+    }
+
+    #[test]
+    fn versao_zero_ou_invalida_e_recusada() {
+        assert!(matches!(ler(&com_versao(0.into())), Err(ErroEstado::FormatoInvalido)));
+        assert!(matches!(ler(&com_versao("2".into())), Err(ErroEstado::FormatoInvalido)));
+        assert!(matches!(ler("[1, 2]"), Err(ErroEstado::FormatoInvalido)));
+        assert!(matches!(ler("{lixo"), Err(ErroEstado::Json(_))));
+    }
+}
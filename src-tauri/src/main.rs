@@ -9,10 +9,20 @@ mod services;
 mod api;
 
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use tauri::Manager;
 use services::prova_service::ProvaService;
-use services::simulado_service::SimuladoService;
-use api::commands::{ProvaServiceWrapper, SimuladoServiceWrapper};
+use services::simulado_service::{PoliticaRecuperacao, SimuladoService};
+use services::edicao_service::EdicaoService;
+use services::caderno_service::CadernoService;
+use services::revisao_service::RevisaoService;
+use services::sisu_service::SisuService;
+use services::backup_service::BackupService;
+use services::perfil_service::PerfilService;
+use api::commands::{
+    CadernoServiceWrapper, EdicaoServiceWrapper, ProvaServiceWrapper, RevisaoServiceWrapper, SimuladoServiceWrapper,
+    SisuServiceWrapper, BackupServiceWrapper, PerfilServiceWrapper,
+};
 
 fn main() {
     tauri::Builder::default()
@@ -63,18 +73,86 @@ fn main() {
             // Inicializa o banco
             let conn = persistence::sqlite::connect(&db_path)
                 .expect("Falha ao conectar ao banco");
+            let conn = Arc::new(Mutex::new(conn));
+
+            // Snapshot diário do banco (os mais antigos são descartados)
+            let backups_dir = db_path.parent()
+                .expect("Caminho do banco sem diretório")
+                .join("backups");
+            let backup_service = BackupService::new(conn.clone(), db_path.clone(), backups_dir);
+            match backup_service.snapshot_diario() {
+                Ok(Some(caminho)) => println!("💾 Snapshot diário criado: {:?}", caminho),
+                Ok(None) => {}
+                Err(e) => println!("⚠️ Falha ao criar snapshot diário: {}", e),
+            }
+            app.manage(BackupServiceWrapper(Arc::new(backup_service)));
 
             // Serviço de provas
             let prova_service = ProvaService::new(provas_dir.clone());
-            app.manage(ProvaServiceWrapper(std::sync::Arc::new(prova_service)));
+            app.manage(ProvaServiceWrapper(Arc::new(prova_service)));
 
             // Serviço de simulados -  Corrigido: passa provas_dir como segundo parâmetro
-            let simulado_repo = persistence::repository::SimuladoRepository::new(conn);
+            let simulado_repo = persistence::repository::SimuladoRepository::new(conn.clone());
             let simulado_service = SimuladoService::new(simulado_repo, provas_dir.clone());
-            app.manage(SimuladoServiceWrapper(std::sync::Arc::new(simulado_service)));
+
+            // Simulados que ficaram em andamento após um fechamento inesperado
+            let politica = simulado_service.politica_recuperacao().unwrap_or_else(|e| {
+                println!("⚠️ Falha ao ler política de recuperação: {}", e);
+                PoliticaRecuperacao::default()
+            });
+            match simulado_service.recuperar_simulados_interrompidos(politica) {
+                Ok(ids) if !ids.is_empty() => println!("🩹 Simulados recuperados: {:?}", ids),
+                Ok(_) => {}
+                Err(e) => println!("⚠️ Falha ao recuperar simulados interrompidos: {}", e),
+            }
+
+            // Apaga de vez o que passou do prazo na lixeira
+            match simulado_service.purgar_lixeira() {
+                Ok(ids) if !ids.is_empty() => println!("🧹 Simulados purgados da lixeira: {:?}", ids),
+                Ok(_) => {}
+                Err(e) => println!("⚠️ Falha ao purgar lixeira: {}", e),
+            }
+
+            let simulado_service = Arc::new(simulado_service);
+            app.manage(SimuladoServiceWrapper(simulado_service.clone()));
+
+            // Perfis locais: simulados, edições, caderno e revisões ficam restritos ao perfil ativo
+            let perfil_repo = persistence::perfil_repository::PerfilRepository::new(conn.clone());
+            let perfil_service = PerfilService::new(perfil_repo, simulado_service.clone());
+            app.manage(PerfilServiceWrapper(Arc::new(perfil_service)));
+
+            // Serviço de edições (simulados de vários dias do mesmo vestibular)
+            let edicao_repo = persistence::edicao_repository::EdicaoRepository::new(conn.clone());
+            let edicao_service = Arc::new(EdicaoService::new(edicao_repo, simulado_service.clone(), provas_dir.clone()));
+            app.manage(EdicaoServiceWrapper(edicao_service.clone()));
+
+            // Simulação do SISU com a base de cursos importada para a pasta de dados
+            let sisu_dir = db_path.parent()
+                .expect("Caminho do banco sem diretório")
+                .join("sisu");
+            app.manage(SisuServiceWrapper(Arc::new(SisuService::new(sisu_dir, edicao_service))));
+
+            // Caderno de erros (alimentado na finalização de cada simulado)
+            let caderno_repo = persistence::caderno_repository::CadernoRepository::new(conn.clone());
+            let caderno_service = CadernoService::new(caderno_repo, provas_dir.clone());
+            app.manage(CadernoServiceWrapper(Arc::new(caderno_service)));
+
+            // Revisão espaçada (SM-2) das questões da biblioteca
+            let revisao_repo = persistence::revisao_repository::RevisaoRepository::new(conn.clone());
+            let revisao_service = RevisaoService::new(revisao_repo, provas_dir.clone());
+            app.manage(RevisaoServiceWrapper(Arc::new(revisao_service)));
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Pausa simulados ativos ao fechar a janela para não cobrar o tempo offline
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                let service = window.state::<SimuladoServiceWrapper>();
+                if let Err(e) = service.0.pausar_simulados_ativos() {
+                    println!("⚠️ Falha ao pausar simulados no fechamento: {}", e);
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // === Comandos para Provas ===
             api::commands::listar_provas,
@@ -85,18 +163,72 @@ fn main() {
             api::commands::pausar_simulado,
             api::commands::retomar_simulado,
             api::commands::atualizar_tempo_simulado,
+            api::commands::configurar_alertas_tempo,
             
             // === Comandos para Simulados - Funcionalidades Essenciais ===
             api::commands::obter_estado_simulado,
             api::commands::responder_questao,
+            api::commands::alternar_alternativa_eliminada,
             api::commands::avancar_questao,
             api::commands::voltar_questao,
             api::commands::finalizar_simulado,
+            api::commands::abandonar_simulado,
+            api::commands::revisar_simulado,
             api::commands::obter_resultado,
+            api::commands::corrigir_folha_respostas,
+            api::commands::comparar_tentativas,
+            api::commands::estatisticas_questao,
+            api::commands::exportar_boletim,
+            api::commands::exportar_historico,
             
             // === Comandos para Simulados - Opcionais ===
             api::commands::listar_simulados,
+            api::commands::listar_simulados_retomaveis,
             api::commands::excluir_simulado,
+            api::commands::listar_lixeira,
+            api::commands::restaurar_simulado,
+            api::commands::excluir_simulado_definitivamente,
+            api::commands::obter_dias_lixeira,
+            api::commands::configurar_dias_lixeira,
+            api::commands::obter_politica_recuperacao,
+            api::commands::configurar_politica_recuperacao,
+            
+            // === Comandos para Edições ===
+            api::commands::criar_edicao,
+            api::commands::vincular_simulado_edicao,
+            api::commands::obter_edicao,
+            api::commands::listar_edicoes,
+
+            // === Comandos do Caderno de Erros ===
+            api::commands::listar_caderno_erros,
+            api::commands::iniciar_treino_caderno,
+            api::commands::responder_treino_caderno,
+            api::commands::obter_configuracao_caderno,
+            api::commands::configurar_caderno,
+
+            // === Comandos da Revisão Espaçada ===
+            api::commands::revisao_do_dia,
+            api::commands::responder_revisao,
+            api::commands::adicionar_questoes_revisao,
+
+            // === Comandos do SISU ===
+            api::commands::importar_cursos_sisu,
+            api::commands::listar_cursos_sisu,
+            api::commands::simular_sisu,
+
+            // === Comandos de Backup ===
+            api::commands::exportar_backup,
+            api::commands::restaurar_backup,
+            api::commands::listar_backups,
+
+            // === Comandos de Perfis ===
+            api::commands::listar_perfis,
+            api::commands::obter_perfil_ativo,
+            api::commands::criar_perfil,
+            api::commands::renomear_perfil,
+            api::commands::definir_pin_perfil,
+            api::commands::trocar_perfil,
+            api::commands::excluir_perfil,
             
             // === Comandos Adicionais ===
             api::commands::questao_existe,
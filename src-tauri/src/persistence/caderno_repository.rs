@@ -0,0 +1,345 @@
+use rusqlite::{Connection, OptionalExtension as _, Result as RusqliteResult, params};
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use crate::domain::caderno::{ConfiguracaoCaderno, EntradaCaderno, FiltroCaderno};
+use crate::domain::resultado::ResultadoSimulado;
+use crate::persistence::configuracao;
+use crate::persistence::perfil_repository;
+
+const CHAVE_INCLUIR_BRANCOS: &str = "caderno.incluir_brancos";
+const CHAVE_ACERTOS_PARA_REMOVER: &str = "caderno.acertos_para_remover";
+
+/// Esconde as entradas que apontam para um simulado na lixeira. Ao ir para a
+/// lixeira o simulado passa suas entradas para o erro de outro simulado; só ficam
+/// apontando para ele as questões que nenhum simulado fora da lixeira errou.
+const FORA_DA_LIXEIRA: &str =
+    "simulado_id NOT IN (SELECT id FROM simulado WHERE excluido_em IS NOT NULL)";
+
+pub struct CadernoRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl CadernoRepository {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    pub fn configuracao(&self) -> RusqliteResult<ConfiguracaoCaderno> {
+        let conn = self.conn.lock().unwrap();
+        Self::configuracao_em(&conn)
+    }
+
+    pub fn salvar_configuracao(&self, config: &ConfiguracaoCaderno) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        configuracao::gravar(&conn, CHAVE_INCLUIR_BRANCOS, &config.incluir_brancos.to_string())?;
+        configuracao::gravar(&conn, CHAVE_ACERTOS_PARA_REMOVER, &config.acertos_para_remover.to_string())
+    }
+
+    fn configuracao_em(conn: &Connection) -> RusqliteResult<ConfiguracaoCaderno> {
+        let padrao = ConfiguracaoCaderno::default();
+        Ok(ConfiguracaoCaderno {
+            incluir_brancos: configuracao::ler_ou(conn, CHAVE_INCLUIR_BRANCOS, padrao.incluir_brancos)?,
+            acertos_para_remover: configuracao::ler_ou(conn, CHAVE_ACERTOS_PARA_REMOVER, padrao.acertos_para_remover)?,
+        })
+    }
+
+    /// Atualiza o caderno com um simulado finalizado: erros entram (ou voltam a
+    /// zero acertos), acertos em questões já anotadas contam para a remoção.
+    /// Chamado dentro da transação de finalização.
+    pub fn registrar_resultado_em(conn: &Connection, prova_id: &str, resultado: &ResultadoSimulado) -> RusqliteResult<()> {
+        let config = Self::configuracao_em(conn)?;
+        let usuario_id = perfil_repository::dono_do_simulado_em(conn, resultado.simulado_id)?;
+        
+        for detalhe in &resultado.detalhes {
+            let em_branco = detalhe.resposta_usuario.is_none();
+            if detalhe.acertou {
+                let id: Option<i64> = conn
+                    .query_row(
+                        "SELECT id FROM caderno_erro WHERE usuario_id = ? AND prova_id = ? AND questao_id = ?",
+                        params![usuario_id, prova_id, &detalhe.questao_id],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if let Some(id) = id {
+                    Self::registrar_acerto_em(conn, id, config.acertos_para_remover)?;
+                }
+            } else if !em_branco || config.incluir_brancos {
+                Self::registrar_erro_em(
+                    conn,
+                    usuario_id,
+                    prova_id,
+                    &detalhe.questao_id,
+                    &detalhe.area_id,
+                    resultado.simulado_id,
+                    detalhe.resposta_usuario.as_deref(),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn registrar_erro_em(
+        conn: &Connection,
+        usuario_id: i64,
+        prova_id: &str,
+        questao_id: &str,
+        area_id: &str,
+        simulado_id: i64,
+        alternativa_marcada: Option<&str>,
+    ) -> RusqliteResult<()> {
+        conn.execute(
+            "INSERT INTO caderno_erro (
+                usuario_id, prova_id, questao_id, area_id, simulado_id, alternativa_marcada, errado_em
+            ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (usuario_id, prova_id, questao_id) DO UPDATE SET
+                simulado_id = excluded.simulado_id,
+                alternativa_marcada = excluded.alternativa_marcada,
+                errado_em = excluded.errado_em,
+                vezes_errada = vezes_errada + 1,
+                acertos_seguidos = 0",
+            params![usuario_id, prova_id, questao_id, area_id, simulado_id, alternativa_marcada, Utc::now()],
+        )?;
+        Ok(())
+    }
+
+    /// Simulado indo para a lixeira (`definitivo = false`) ou sendo apagado. Cada entrada
+    /// junta todos os erros da questão no perfil, então as que apontavam para ele passam
+    /// para o erro mais recente da questão em outro simulado fora da lixeira. Na exclusão
+    /// definitiva o erro também sai de `vezes_errada`, e a entrada só é apagada quando
+    /// nenhum outro simulado errou a questão. Chamado antes de apagar as respostas.
+    pub fn remover_simulado_em(conn: &Connection, simulado_id: i64, definitivo: bool) -> RusqliteResult<()> {
+        let incluir_brancos = Self::configuracao_em(conn)?.incluir_brancos;
+        
+        for (id, apontada) in Self::entradas_do_simulado_em(conn, simulado_id, incluir_brancos)? {
+            let ultimo = Self::ultimo_erro_em(conn, id, Some(simulado_id), incluir_brancos)?;
+            if definitivo {
+                if apontada == simulado_id && ultimo.is_none() {
+                    conn.execute("DELETE FROM caderno_erro WHERE id = ?", [id])?;
+                    continue;
+                }
+                conn.execute(
+                    "UPDATE caderno_erro SET vezes_errada = MAX(vezes_errada - 1, 1) WHERE id = ?",
+                    [id],
+                )?;
+            }
+            if let Some((outro, alternativa)) = ultimo.filter(|_| apontada == simulado_id) {
+                conn.execute(
+                    "UPDATE caderno_erro SET simulado_id = ?, alternativa_marcada = ? WHERE id = ?",
+                    params![outro, alternativa, id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Simulado saiu da lixeira: as entradas das questões que ele errou voltam a
+    /// apontar para o erro mais recente, que pode ser o dele
+    pub fn restaurar_simulado_em(conn: &Connection, simulado_id: i64) -> RusqliteResult<()> {
+        let incluir_brancos = Self::configuracao_em(conn)?.incluir_brancos;
+        
+        for (id, _) in Self::entradas_do_simulado_em(conn, simulado_id, incluir_brancos)? {
+            if let Some((ultimo, alternativa)) = Self::ultimo_erro_em(conn, id, None, incluir_brancos)? {
+                conn.execute(
+                    "UPDATE caderno_erro SET simulado_id = ?, alternativa_marcada = ? WHERE id = ?",
+                    params![ultimo, alternativa, id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Entradas (id e simulado apontado) das questões erradas no simulado, mais as
+    /// que apontam para ele sem linha em `resposta`
+    fn entradas_do_simulado_em(conn: &Connection, simulado_id: i64, incluir_brancos: bool) -> RusqliteResult<Vec<(i64, i64)>> {
+        conn.prepare(
+            "SELECT c.id, c.simulado_id
+             FROM caderno_erro c
+             JOIN simulado s ON s.id = ?1
+             WHERE c.usuario_id = s.usuario_id AND c.prova_id = s.prova_id
+               AND (c.simulado_id = ?1 OR EXISTS (
+                   SELECT 1 FROM resposta r
+                   WHERE r.simulado_id = ?1 AND r.questao_id = c.questao_id AND r.correta = 0
+                     AND (r.alternativa_marcada IS NOT NULL OR ?2)
+               ))"
+        )?
+        .query_map(params![simulado_id, incluir_brancos], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect()
+    }
+
+    /// Simulado fora da lixeira (e diferente de `exceto`) com o erro mais recente na
+    /// questão da entrada, e a alternativa marcada nele
+    fn ultimo_erro_em(
+        conn: &Connection,
+        entrada_id: i64,
+        exceto: Option<i64>,
+        incluir_brancos: bool,
+    ) -> RusqliteResult<Option<(i64, Option<String>)>> {
+        conn.query_row(
+            "SELECT s.id, r.alternativa_marcada
+             FROM caderno_erro c
+             JOIN simulado s ON s.usuario_id = c.usuario_id AND s.prova_id = c.prova_id
+             JOIN resposta r ON r.simulado_id = s.id AND r.questao_id = c.questao_id
+             WHERE c.id = ?1 AND s.id IS NOT ?2 AND s.excluido_em IS NULL
+               AND r.correta = 0 AND (r.alternativa_marcada IS NOT NULL OR ?3)
+             ORDER BY s.id DESC
+             LIMIT 1",
+            params![entrada_id, exceto, incluir_brancos],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+    }
+
+    /// Soma um acerto; ao atingir `acertos_para_remover` a entrada sai do caderno.
+    /// Retorna `true` se a entrada foi removida.
+    fn registrar_acerto_em(conn: &Connection, id: i64, acertos_para_remover: u32) -> RusqliteResult<bool> {
+        conn.execute(
+            "UPDATE caderno_erro SET acertos_seguidos = acertos_seguidos + 1 WHERE id = ?",
+            [id],
+        )?;
+        let removidas = conn.execute(
+            "DELETE FROM caderno_erro WHERE id = ? AND acertos_seguidos >= ?",
+            params![id, acertos_para_remover],
+        )?;
+        Ok(removidas > 0)
+    }
+
+    /// Acerto no treino do caderno. Retorna `true` se a entrada foi removida.
+    pub fn registrar_acerto(&self, id: i64) -> RusqliteResult<bool> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let config = Self::configuracao_em(&tx)?;
+        let removida = Self::registrar_acerto_em(&tx, id, config.acertos_para_remover)?;
+        tx.commit()?;
+        Ok(removida)
+    }
+
+    /// Erro no treino do caderno: zera os acertos seguidos sem trocar o simulado de origem
+    pub fn registrar_erro_treino(&self, id: i64, alternativa_marcada: &str) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE caderno_erro SET
+                alternativa_marcada = ?, errado_em = ?,
+                vezes_errada = vezes_errada + 1, acertos_seguidos = 0
+             WHERE id = ?",
+            params![alternativa_marcada, Utc::now(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Entrada do caderno do perfil ativo
+    pub fn buscar_por_id(&self, id: i64) -> RusqliteResult<Option<EntradaCaderno>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        conn
+            .query_row(
+                &format!("SELECT * FROM caderno_erro WHERE id = ? AND usuario_id = ? AND {}", FORA_DA_LIXEIRA),
+                params![id, usuario_id],
+                EntradaCaderno::from_row,
+            )
+            .optional()
+    }
+
+    /// Entradas do caderno do perfil ativo, as mais recentes primeiro
+    pub fn listar(&self, filtro: &FiltroCaderno) -> RusqliteResult<Vec<EntradaCaderno>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT * FROM caderno_erro
+             WHERE usuario_id = ?3 AND (?1 IS NULL OR area_id = ?1) AND (?2 IS NULL OR prova_id = ?2)
+               AND {}
+             ORDER BY errado_em DESC",
+            FORA_DA_LIXEIRA,
+        ))?;
+        let entradas = stmt
+            .query_map(params![&filtro.area_id, &filtro.prova_id, usuario_id], EntradaCaderno::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entradas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::repository::SimuladoRepository;
+    use crate::persistence::sqlite;
+    use std::path::Path;
+
+    const PROVA: &str = "enem/2023_dia1";
+
+    fn banco() -> Arc<Mutex<Connection>> {
+        let mut conn = Connection::open_in_memory().unwrap();
+        sqlite::migrar(&mut conn, Path::new(":memory:")).unwrap();
+        Arc::new(Mutex::new(conn))
+    }
+
+    /// Simulado finalizado que errou a Q05 marcando `alternativa`
+    fn simulado_com_erro(conn: &Connection, alternativa: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO simulado (usuario_id, vestibular, ano, prova_id, tempo_limite, estado_json)
+             VALUES (1, 'ENEM', 2023, ?, 300, '{}')",
+            [PROVA],
+        ).unwrap();
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO resposta (simulado_id, questao_id, alternativa_marcada, correta) VALUES (?, 'Q05', ?, 0)",
+            params![id, alternativa],
+        ).unwrap();
+        CadernoRepository::registrar_erro_em(conn, 1, PROVA, "Q05", "LC", id, Some(alternativa)).unwrap();
+        id
+    }
+
+    /// Simulado apontado, alternativa e vezes errada da entrada da Q05
+    fn entrada(conn: &Arc<Mutex<Connection>>) -> Option<(i64, String, u32)> {
+        conn.lock().unwrap()
+            .query_row(
+                "SELECT simulado_id, alternativa_marcada, vezes_errada FROM caderno_erro WHERE questao_id = 'Q05'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()
+            .unwrap()
+    }
+
+    #[test]
+    fn excluir_um_simulado_mantem_os_erros_dos_outros() {
+        let conn = banco();
+        let (primeiro, segundo) = {
+            let conn = conn.lock().unwrap();
+            (simulado_com_erro(&conn, "A"), simulado_com_erro(&conn, "B"))
+        };
+        let simulados = SimuladoRepository::new(conn.clone());
+        let caderno = CadernoRepository::new(conn.clone());
+        assert_eq!(entrada(&conn), Some((segundo, "B".to_string(), 2)));
+        
+        // Na lixeira: a entrada segue visível, com o erro do outro simulado
+        simulados.marcar_excluido(segundo, Some(Utc::now())).unwrap();
+        assert_eq!(entrada(&conn), Some((primeiro, "A".to_string(), 2)));
+        assert_eq!(caderno.listar(&FiltroCaderno::default()).unwrap().len(), 1);
+        
+        simulados.marcar_excluido(segundo, None).unwrap();
+        assert_eq!(entrada(&conn), Some((segundo, "B".to_string(), 2)));
+        
+        // Exclusão definitiva tira só o erro daquele simulado
+        simulados.excluir_definitivamente(segundo).unwrap();
+        assert_eq!(entrada(&conn), Some((primeiro, "A".to_string(), 1)));
+        assert_eq!(caderno.listar(&FiltroCaderno::default()).unwrap().len(), 1);
+        
+        simulados.excluir_definitivamente(primeiro).unwrap();
+        assert_eq!(entrada(&conn), None);
+    }
+
+    #[test]
+    fn entrada_sem_outro_erro_fica_escondida_na_lixeira() {
+        let conn = banco();
+        let id = simulado_com_erro(&conn.lock().unwrap(), "C");
+        let simulados = SimuladoRepository::new(conn.clone());
+        let caderno = CadernoRepository::new(conn.clone());
+        
+        simulados.marcar_excluido(id, Some(Utc::now())).unwrap();
+        assert!(caderno.listar(&FiltroCaderno::default()).unwrap().is_empty());
+        assert_eq!(entrada(&conn), Some((id, "C".to_string(), 1)));
+        
+        simulados.marcar_excluido(id, None).unwrap();
+        assert_eq!(caderno.listar(&FiltroCaderno::default()).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,23 @@
+use rusqlite::{Connection, OptionalExtension as _, Result as RusqliteResult, params};
+
+/// Preferências do usuário em pares chave/valor (tabela `configuracao`).
+/// Recebem a conexão para poderem ser usadas dentro de transações.
+pub fn ler(conn: &Connection, chave: &str) -> RusqliteResult<Option<String>> {
+    conn.query_row("SELECT valor FROM configuracao WHERE chave = ?", [chave], |row| row.get(0))
+        .optional()
+}
+
+pub fn gravar(conn: &Connection, chave: &str, valor: &str) -> RusqliteResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO configuracao (chave, valor) VALUES (?, ?)",
+        params![chave, valor],
+    )?;
+    Ok(())
+}
+
+/// Lê e converte o valor; ausente ou inválido devolve `padrao`
+pub fn ler_ou<T: std::str::FromStr>(conn: &Connection, chave: &str, padrao: T) -> RusqliteResult<T> {
+    Ok(ler(conn, chave)?
+        .and_then(|valor| valor.parse().ok())
+        .unwrap_or(padrao))
+}
@@ -0,0 +1,93 @@
+use rusqlite::{Connection, OptionalExtension as _, Result as RusqliteResult, params};
+use std::sync::{Arc, Mutex};
+use crate::domain::edicao::{Edicao, VinculoEdicao};
+use crate::persistence::perfil_repository;
+
+pub struct EdicaoRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl EdicaoRepository {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    pub fn criar(&self, vestibular: &str, ano: i32) -> RusqliteResult<i64> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        conn.execute(
+            "INSERT INTO edicao (vestibular, ano, usuario_id) VALUES (?, ?, ?)",
+            params![vestibular, ano, usuario_id],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Edição do perfil ativo
+    pub fn buscar_por_id(&self, id: i64) -> RusqliteResult<Option<Edicao>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        conn
+            .query_row(
+                "SELECT * FROM edicao WHERE id = ? AND usuario_id = ?",
+                params![id, usuario_id],
+                Edicao::from_row,
+            )
+            .optional()
+    }
+
+    /// Edições do perfil ativo
+    pub fn listar_todas(&self) -> RusqliteResult<Vec<Edicao>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM edicao WHERE usuario_id = ? ORDER BY ano DESC, criada_em DESC"
+        )?;
+        let edicoes = stmt
+            .query_map([usuario_id], Edicao::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(edicoes)
+    }
+
+    /// Vincula o simulado ao dia da edição. Um novo simulado para o mesmo dia
+    /// substitui o anterior (refazer o dia 1, por exemplo). Um simulado vinculado
+    /// a outra edição viola o `UNIQUE` de `simulado_id` e dá erro.
+    pub fn vincular(&self, edicao_id: i64, dia: u8, simulado_id: i64) -> RusqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM edicao_simulado WHERE edicao_id = ? AND dia = ?",
+            params![edicao_id, dia],
+        )?;
+        tx.execute(
+            "INSERT INTO edicao_simulado (edicao_id, dia, simulado_id) VALUES (?, ?, ?)",
+            params![edicao_id, dia, simulado_id],
+        )?;
+        tx.commit()
+    }
+
+    pub fn edicao_do_simulado(&self, simulado_id: i64) -> RusqliteResult<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        conn
+            .query_row(
+                "SELECT edicao_id FROM edicao_simulado WHERE simulado_id = ?",
+                [simulado_id],
+                |row| row.get(0),
+            )
+            .optional()
+    }
+
+    /// Vínculos da edição, sem os simulados que estão na lixeira
+    pub fn listar_vinculos(&self, edicao_id: i64) -> RusqliteResult<Vec<VinculoEdicao>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT es.* FROM edicao_simulado es
+             JOIN simulado s ON s.id = es.simulado_id
+             WHERE es.edicao_id = ? AND s.excluido_em IS NULL
+             ORDER BY es.dia"
+        )?;
+        let vinculos = stmt
+            .query_map([edicao_id], VinculoEdicao::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(vinculos)
+    }
+}
@@ -0,0 +1,309 @@
+//! Migrações numeradas do banco. A versão aplicada fica em `PRAGMA user_version`;
+//! cada migração roda na sua própria transação, junto com a troca de versão.
+//!
+//! Para mudar o esquema, acrescente uma nova migração no fim de `MIGRACOES`.
+//! Nunca altere uma migração já publicada: bancos de usuários já passaram por ela.
+//! As primeiras usam `IF NOT EXISTS` porque bancos anteriores ao versionamento
+//! (versão 0) podem ter qualquer parte desse esquema.
+
+use rusqlite::{Connection, Result as RusqliteResult};
+
+pub struct Migracao {
+    pub versao: u32,
+    pub descricao: &'static str,
+    pub aplicar: fn(&Connection) -> RusqliteResult<()>,
+}
+
+pub const MIGRACOES: &[Migracao] = &[
+    Migracao { versao: 1, descricao: "esquema inicial", aplicar: v1_esquema_inicial },
+    Migracao { versao: 2, descricao: "edições de vários dias", aplicar: v2_edicoes },
+    Migracao { versao: 3, descricao: "snapshot do resultado", aplicar: v3_snapshot_resultado },
+    Migracao { versao: 4, descricao: "configuração e caderno de erros", aplicar: v4_caderno_erros },
+    Migracao { versao: 5, descricao: "revisão espaçada", aplicar: v5_revisao_espacada },
+    Migracao { versao: 6, descricao: "uma linha por resposta", aplicar: v6_respostas_normalizadas },
+    Migracao { versao: 7, descricao: "lixeira de simulados", aplicar: v7_lixeira },
+    Migracao { versao: 8, descricao: "perfis de usuário", aplicar: v8_perfis },
+];
+
+/// Versão de esquema que este build do app sabe usar
+pub fn versao_atual() -> u32 {
+    MIGRACOES.last().map_or(0, |m| m.versao)
+}
+
+fn v1_esquema_inicial(conn: &Connection) -> RusqliteResult<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS usuario (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            nome TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS simulado (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vestibular TEXT NOT NULL,
+            ano INTEGER NOT NULL,
+            prova_id TEXT NOT NULL,
+            tempo_limite INTEGER NOT NULL,
+            iniciado_em DATETIME,
+            finalizado_em DATETIME,
+            estado_json TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS resposta (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            simulado_id INTEGER NOT NULL,
+            questao_id TEXT NOT NULL,
+            alternativa_marcada TEXT,
+            correta BOOLEAN,
+            FOREIGN KEY (simulado_id) REFERENCES simulado(id)
+        );
+
+        CREATE TABLE IF NOT EXISTS resultado (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            simulado_id INTEGER NOT NULL UNIQUE,
+            total_questoes INTEGER,
+            acertos INTEGER,
+            erros INTEGER,
+            pontuacao REAL,
+            FOREIGN KEY (simulado_id) REFERENCES simulado(id)
+        );
+
+        CREATE TABLE IF NOT EXISTS anotacao (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            titulo TEXT,
+            conteudo TEXT NOT NULL,
+            criada_em DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS agenda_evento (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            titulo TEXT NOT NULL,
+            descricao TEXT,
+            data_inicio DATETIME NOT NULL,
+            data_fim DATETIME
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_resposta_simulado ON resposta(simulado_id);
+        CREATE INDEX IF NOT EXISTS idx_simulado_vestibular ON simulado(vestibular);
+        "
+    )
+}
+
+fn v2_edicoes(conn: &Connection) -> RusqliteResult<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS edicao (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            vestibular TEXT NOT NULL,
+            ano INTEGER NOT NULL,
+            criada_em DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+
+        CREATE TABLE IF NOT EXISTS edicao_simulado (
+            edicao_id INTEGER NOT NULL,
+            dia INTEGER NOT NULL,
+            simulado_id INTEGER NOT NULL UNIQUE,
+            PRIMARY KEY (edicao_id, dia),
+            FOREIGN KEY (edicao_id) REFERENCES edicao(id),
+            FOREIGN KEY (simulado_id) REFERENCES simulado(id)
+        );
+        "
+    )
+}
+
+fn v3_snapshot_resultado(conn: &Connection) -> RusqliteResult<()> {
+    adicionar_coluna_se_ausente(conn, "resultado", "brancos", "INTEGER")?;
+    adicionar_coluna_se_ausente(conn, "resultado", "resultado_json", "TEXT")?;
+    adicionar_coluna_se_ausente(conn, "resultado", "calculado_em", "DATETIME")
+}
+
+fn v4_caderno_erros(conn: &Connection) -> RusqliteResult<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS configuracao (
+            chave TEXT PRIMARY KEY,
+            valor TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS caderno_erro (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            prova_id TEXT NOT NULL,
+            questao_id TEXT NOT NULL,
+            area_id TEXT NOT NULL,
+            simulado_id INTEGER NOT NULL,
+            alternativa_marcada TEXT,
+            errado_em DATETIME NOT NULL,
+            vezes_errada INTEGER NOT NULL DEFAULT 1,
+            acertos_seguidos INTEGER NOT NULL DEFAULT 0,
+            UNIQUE (prova_id, questao_id),
+            FOREIGN KEY (simulado_id) REFERENCES simulado(id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_caderno_area ON caderno_erro(area_id);
+        "
+    )
+}
+
+fn v5_revisao_espacada(conn: &Connection) -> RusqliteResult<()> {
+    conn.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS revisao_questao (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            prova_id TEXT NOT NULL,
+            questao_id TEXT NOT NULL,
+            area_id TEXT NOT NULL,
+            repeticoes INTEGER NOT NULL DEFAULT 0,
+            intervalo_dias INTEGER NOT NULL DEFAULT 0,
+            facilidade REAL NOT NULL DEFAULT 2.5,
+            proxima_revisao DATE NOT NULL,
+            ultima_revisao DATETIME,
+            ultima_nota INTEGER,
+            UNIQUE (prova_id, questao_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_revisao_proxima ON revisao_questao(proxima_revisao);
+        "
+    )
+}
+
+/// `resposta` passa a ter uma linha por questão de cada simulado, atualizada a cada
+/// marcação. Remove duplicatas (fica a mais recente) e traz as respostas dos simulados
+/// ainda sem linhas a partir do `estado_json`; `correta` fica nula até a finalização.
+fn v6_respostas_normalizadas(conn: &Connection) -> RusqliteResult<()> {
+    conn.execute_batch(
+        "
+        ALTER TABLE resposta ADD COLUMN marcada_em DATETIME;
+
+        DELETE FROM resposta WHERE id NOT IN (
+            SELECT MAX(id) FROM resposta GROUP BY simulado_id, questao_id
+        );
+
+        CREATE UNIQUE INDEX idx_resposta_simulado_questao ON resposta(simulado_id, questao_id);
+        "
+    )?;
+
+    let simulados = conn
+        .prepare("SELECT id, estado_json FROM simulado")?
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut inserir = conn.prepare(
+        "INSERT OR IGNORE INTO resposta (simulado_id, questao_id, alternativa_marcada) VALUES (?, ?, ?)"
+    )?;
+    for (simulado_id, estado_json) in simulados {
+        // Estado ilegível fica como está; o app o mostra como corrompido
+        let Ok(estado) = serde_json::from_str::<serde_json::Value>(&estado_json) else {
+            continue;
+        };
+        let Some(respostas) = estado.get("respostas").and_then(|r| r.as_object()) else {
+            continue;
+        };
+        for (questao_id, alternativa) in respostas {
+            if let Some(alternativa) = alternativa.as_str() {
+                inserir.execute(rusqlite::params![simulado_id, questao_id, alternativa])?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn v7_lixeira(conn: &Connection) -> RusqliteResult<()> {
+    conn.execute_batch(
+        "
+        ALTER TABLE simulado ADD COLUMN excluido_em DATETIME;
+        CREATE INDEX idx_simulado_excluido ON simulado(excluido_em);
+        "
+    )
+}
+
+/// Cada perfil (`usuario`) passa a ter seus próprios simulados, edições, anotações,
+/// caderno de erros e revisões. Cria o perfil "Principal" quando não há nenhum e
+/// entrega a ele tudo o que já existia. `caderno_erro` e `revisao_questao` são
+/// recriadas porque a unicidade por questão passa a incluir o perfil.
+fn v8_perfis(conn: &Connection) -> RusqliteResult<()> {
+    conn.execute_batch(
+        "
+        ALTER TABLE usuario ADD COLUMN pin_hash TEXT;
+        INSERT INTO usuario (nome) SELECT 'Principal' WHERE NOT EXISTS (SELECT 1 FROM usuario);
+        UPDATE usuario SET nome = 'Perfil ' || id WHERE nome IS NULL OR trim(nome) = '';
+
+        ALTER TABLE simulado ADD COLUMN usuario_id INTEGER REFERENCES usuario(id);
+        ALTER TABLE edicao ADD COLUMN usuario_id INTEGER REFERENCES usuario(id);
+        ALTER TABLE anotacao ADD COLUMN usuario_id INTEGER REFERENCES usuario(id);
+        UPDATE simulado SET usuario_id = (SELECT MIN(id) FROM usuario);
+        UPDATE edicao SET usuario_id = (SELECT MIN(id) FROM usuario);
+        UPDATE anotacao SET usuario_id = (SELECT MIN(id) FROM usuario);
+        CREATE INDEX idx_simulado_usuario ON simulado(usuario_id);
+
+        CREATE TABLE caderno_erro_novo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            usuario_id INTEGER NOT NULL,
+            prova_id TEXT NOT NULL,
+            questao_id TEXT NOT NULL,
+            area_id TEXT NOT NULL,
+            simulado_id INTEGER NOT NULL,
+            alternativa_marcada TEXT,
+            errado_em DATETIME NOT NULL,
+            vezes_errada INTEGER NOT NULL DEFAULT 1,
+            acertos_seguidos INTEGER NOT NULL DEFAULT 0,
+            UNIQUE (usuario_id, prova_id, questao_id),
+            FOREIGN KEY (usuario_id) REFERENCES usuario(id),
+            FOREIGN KEY (simulado_id) REFERENCES simulado(id)
+        );
+        INSERT INTO caderno_erro_novo (
+            id, usuario_id, prova_id, questao_id, area_id, simulado_id,
+            alternativa_marcada, errado_em, vezes_errada, acertos_seguidos
+        )
+        SELECT id, (SELECT MIN(id) FROM usuario), prova_id, questao_id, area_id, simulado_id,
+               alternativa_marcada, errado_em, vezes_errada, acertos_seguidos
+        FROM caderno_erro;
+        DROP TABLE caderno_erro;
+        ALTER TABLE caderno_erro_novo RENAME TO caderno_erro;
+        CREATE INDEX idx_caderno_area ON caderno_erro(area_id);
+
+        CREATE TABLE revisao_questao_novo (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            usuario_id INTEGER NOT NULL,
+            prova_id TEXT NOT NULL,
+            questao_id TEXT NOT NULL,
+            area_id TEXT NOT NULL,
+            repeticoes INTEGER NOT NULL DEFAULT 0,
+            intervalo_dias INTEGER NOT NULL DEFAULT 0,
+            facilidade REAL NOT NULL DEFAULT 2.5,
+            proxima_revisao DATE NOT NULL,
+            ultima_revisao DATETIME,
+            ultima_nota INTEGER,
+            UNIQUE (usuario_id, prova_id, questao_id),
+            FOREIGN KEY (usuario_id) REFERENCES usuario(id)
+        );
+        INSERT INTO revisao_questao_novo (
+            id, usuario_id, prova_id, questao_id, area_id, repeticoes, intervalo_dias,
+            facilidade, proxima_revisao, ultima_revisao, ultima_nota
+        )
+        SELECT id, (SELECT MIN(id) FROM usuario), prova_id, questao_id, area_id, repeticoes, intervalo_dias,
+               facilidade, proxima_revisao, ultima_revisao, ultima_nota
+        FROM revisao_questao;
+        DROP TABLE revisao_questao;
+        ALTER TABLE revisao_questao_novo RENAME TO revisao_questao;
+        CREATE INDEX idx_revisao_proxima ON revisao_questao(proxima_revisao);
+
+        INSERT OR REPLACE INTO configuracao (chave, valor)
+        SELECT 'perfil.ativo', MIN(id) FROM usuario;
+        "
+    )
+}
+
+/// Adiciona a coluna se ela ainda não existir. Só para migrações que precisam
+/// conviver com bancos anteriores ao versionamento; as novas usam `ALTER TABLE` direto.
+fn adicionar_coluna_se_ausente(conn: &Connection, tabela: &str, coluna: &str, definicao: &str) -> RusqliteResult<()> {
+    let colunas = conn
+        .prepare(&format!("PRAGMA table_info({})", tabela))?
+        .query_map([], |row| row.get::<_, String>("name"))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !colunas.iter().any(|nome| nome == coluna) {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", tabela, coluna, definicao))?;
+    }
+    Ok(())
+}
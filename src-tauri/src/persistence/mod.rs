@@ -1,2 +1,8 @@
 pub mod repository;
+pub mod edicao_repository;
 pub mod sqlite;
+pub mod caderno_repository;
+pub mod configuracao;
+pub mod revisao_repository;
+pub mod migracoes;
+pub mod perfil_repository;
\ No newline at end of file
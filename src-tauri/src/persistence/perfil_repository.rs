@@ -0,0 +1,120 @@
+use rusqlite::{Connection, OptionalExtension as _, Result as RusqliteResult, params};
+use std::sync::{Arc, Mutex};
+use crate::domain::perfil::Perfil;
+use crate::persistence::configuracao;
+
+const CHAVE_PERFIL_ATIVO: &str = "perfil.ativo";
+
+/// Perfil ativo. Os demais repositórios filtram por ele a cada consulta, então
+/// trocar de perfil é só gravar outra chave em `configuracao`. Se a chave apontar
+/// para um perfil que não existe mais, vale o mais antigo.
+pub fn ativo_em(conn: &Connection) -> RusqliteResult<i64> {
+    let id: i64 = configuracao::ler_ou(conn, CHAVE_PERFIL_ATIVO, 0)?;
+    if id > 0 {
+        let existe: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM usuario WHERE id = ?)",
+            [id],
+            |row| row.get(0),
+        )?;
+        if existe {
+            return Ok(id);
+        }
+    }
+    conn.query_row("SELECT MIN(id) FROM usuario", [], |row| row.get(0))
+}
+
+/// Perfil a que o simulado pertence. Usado ao finalizar, que também pode
+/// acontecer na recuperação de um simulado de outro perfil.
+pub fn dono_do_simulado_em(conn: &Connection, simulado_id: i64) -> RusqliteResult<i64> {
+    conn.query_row("SELECT usuario_id FROM simulado WHERE id = ?", [simulado_id], |row| row.get(0))
+}
+
+pub struct PerfilRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl PerfilRepository {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    pub fn criar(&self, nome: &str, pin_hash: Option<&str>) -> RusqliteResult<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO usuario (nome, pin_hash) VALUES (?, ?)",
+            params![nome, pin_hash],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn renomear(&self, id: i64, nome: &str) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE usuario SET nome = ? WHERE id = ?", params![nome, id])?;
+        Ok(())
+    }
+
+    /// Define (`Some`) ou remove (`None`) o PIN
+    pub fn definir_pin(&self, id: i64, pin_hash: Option<&str>) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE usuario SET pin_hash = ? WHERE id = ?", params![pin_hash, id])?;
+        Ok(())
+    }
+
+    pub fn buscar_por_id(&self, id: i64) -> RusqliteResult<Option<Perfil>> {
+        let conn = self.conn.lock().unwrap();
+        conn
+            .query_row("SELECT * FROM usuario WHERE id = ?", [id], Perfil::from_row)
+            .optional()
+    }
+
+    pub fn pin_hash(&self, id: i64) -> RusqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let hash: Option<Option<String>> = conn
+            .query_row("SELECT pin_hash FROM usuario WHERE id = ?", [id], |row| row.get(0))
+            .optional()?;
+        Ok(hash.flatten())
+    }
+
+    pub fn listar(&self) -> RusqliteResult<Vec<Perfil>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT * FROM usuario ORDER BY nome COLLATE NOCASE, id")?;
+        let perfis = stmt
+            .query_map([], Perfil::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(perfis)
+    }
+
+    pub fn ativo(&self) -> RusqliteResult<i64> {
+        let conn = self.conn.lock().unwrap();
+        ativo_em(&conn)
+    }
+
+    pub fn ativar(&self, id: i64) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        configuracao::gravar(&conn, CHAVE_PERFIL_ATIVO, &id.to_string())
+    }
+
+    /// Apaga o perfil com todo o histórico dele (simulados, inclusive os da
+    /// lixeira, respostas, resultados, edições, anotações, caderno e revisões)
+    pub fn excluir(&self, id: i64) -> RusqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        let do_perfil = "SELECT id FROM simulado WHERE usuario_id = ?1";
+        tx.execute(&format!("DELETE FROM resposta WHERE simulado_id IN ({})", do_perfil), [id])?;
+        tx.execute(&format!("DELETE FROM resultado WHERE simulado_id IN ({})", do_perfil), [id])?;
+        tx.execute(&format!("DELETE FROM edicao_simulado WHERE simulado_id IN ({})", do_perfil), [id])?;
+        tx.execute(
+            "DELETE FROM edicao_simulado WHERE edicao_id IN (SELECT id FROM edicao WHERE usuario_id = ?)",
+            [id],
+        )?;
+        tx.execute("DELETE FROM edicao WHERE usuario_id = ?", [id])?;
+        tx.execute("DELETE FROM caderno_erro WHERE usuario_id = ?", [id])?;
+        tx.execute("DELETE FROM revisao_questao WHERE usuario_id = ?", [id])?;
+        tx.execute("DELETE FROM anotacao WHERE usuario_id = ?", [id])?;
+        tx.execute("DELETE FROM simulado WHERE usuario_id = ?", [id])?;
+        tx.execute("DELETE FROM usuario WHERE id = ?", [id])?;
+        
+        tx.commit()
+    }
+}
@@ -1,32 +1,210 @@
 use rusqlite::{Connection, Error, OptionalExtension as _, Result as RusqliteResult, params};
-use std::sync::Mutex; // 👈 adicione isto
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex}; // 👈 adicione isto
 use crate::domain::simulado::Simulado;
+use crate::domain::resultado::{AlternativaMarcada, EstatisticaQuestao, ResultadoSimulado};
+use crate::persistence::caderno_repository::CadernoRepository;
+use crate::persistence::revisao_repository::RevisaoRepository;
+use crate::persistence::configuracao;
+use crate::persistence::perfil_repository;
+
+const CHAVE_DIAS_LIXEIRA: &str = "lixeira.dias_retencao";
+const CHAVE_POLITICA_RECUPERACAO: &str = "recuperacao.politica";
+/// Dias na lixeira antes da exclusão definitiva automática
+pub const DIAS_LIXEIRA_PADRAO: u32 = 30;
+/// Maior retenção aceita (10 anos)
+pub const DIAS_LIXEIRA_MAXIMO: u32 = 3650;
 
 pub struct SimuladoRepository {
-    conn: Mutex<Connection>, // 👈 envolva com Mutex
+    conn: Arc<Mutex<Connection>>, // 👈 compartilhada com os demais repositórios
 }
 
 impl SimuladoRepository {
-    pub fn new(conn: Connection) -> Self {
-        Self {
-            conn: Mutex::new(conn),
-        }
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
     }
 
     pub fn salvar(&self, simulado: &Simulado) -> RusqliteResult<i64> {
         let conn = self.conn.lock().unwrap(); // 👈 trava a conexão
+        Self::salvar_em(&conn, simulado)
+    }
+
+    /// Grava o simulado finalizado junto com o snapshot do resultado, uma linha
+    /// por resposta, o caderno de erros e a revisão espaçada, numa única transação. Retorna o ID do simulado.
+    pub fn salvar_finalizado(&self, simulado: &Simulado, resultado: &mut ResultadoSimulado) -> RusqliteResult<i64> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        let id = Self::salvar_em(&tx, simulado)?;
+        resultado.simulado_id = id;
+        Self::gravar_resultado(&tx, resultado)?;
+        CadernoRepository::registrar_resultado_em(&tx, &simulado.prova_id, resultado)?;
+        RevisaoRepository::registrar_resultado_em(&tx, &simulado.prova_id, resultado, chrono::Local::now().date_naive())?;
+        
+        tx.commit()?;
+        Ok(id)
+    }
+
+    /// Grava o estado junto com a linha da resposta marcada, numa transação, para
+    /// que `resposta` e o `estado_json` (cache da interface) nunca divirjam
+    pub fn salvar_com_resposta(
+        &self,
+        simulado: &Simulado,
+        questao_id: &str,
+        alternativa: Option<&str>,
+        correta: Option<bool>,
+    ) -> RusqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        Self::salvar_em(&tx, simulado)?;
+        tx.execute(
+            "INSERT INTO resposta (simulado_id, questao_id, alternativa_marcada, correta, marcada_em)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (simulado_id, questao_id) DO UPDATE SET
+                alternativa_marcada = excluded.alternativa_marcada,
+                correta = excluded.correta,
+                marcada_em = excluded.marcada_em",
+            params![simulado.id, questao_id, alternativa, correta, chrono::Utc::now()],
+        )?;
+        
+        tx.commit()
+    }
+
+    /// Alternativa marcada em cada questão do simulado ("Q01" => Some("A"); `None` quando
+    /// a marcação foi apagada). É a fonte das respostas usada na correção.
+    pub fn respostas(&self, simulado_id: i64) -> RusqliteResult<HashMap<String, Option<String>>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT questao_id, alternativa_marcada FROM resposta WHERE simulado_id = ?"
+        )?;
+        let respostas = stmt
+            .query_map([simulado_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(respostas)
+    }
+
+    /// Quantas vezes a questão foi respondida, acertada e com qual alternativa,
+    /// somando os simulados da prova feitos no perfil ativo que não estão na lixeira.
+    /// Só contam respostas já corrigidas (modo estudo ou simulado finalizado).
+    pub fn estatisticas_questao(&self, prova_id: &str, questao_id: &str) -> RusqliteResult<EstatisticaQuestao> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let mut stmt = conn.prepare(
+            "SELECT r.alternativa_marcada, COUNT(*), SUM(CASE WHEN r.correta THEN 1 ELSE 0 END)
+             FROM resposta r
+             JOIN simulado s ON s.id = r.simulado_id
+             WHERE s.prova_id = ? AND r.questao_id = ? AND r.alternativa_marcada IS NOT NULL
+               AND r.correta IS NOT NULL
+               AND s.usuario_id = ? AND s.excluido_em IS NULL
+             GROUP BY r.alternativa_marcada
+             ORDER BY COUNT(*) DESC, r.alternativa_marcada"
+        )?;
+        let linhas = stmt
+            .query_map(params![prova_id, questao_id, usuario_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?, row.get::<_, u32>(2)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        Ok(EstatisticaQuestao {
+            prova_id: prova_id.to_string(),
+            questao_id: questao_id.to_string(),
+            respondida: linhas.iter().map(|(_, total, _)| total).sum(),
+            acertos: linhas.iter().map(|(_, _, acertos)| acertos).sum(),
+            alternativas: linhas.into_iter()
+                .map(|(alternativa, vezes, _)| AlternativaMarcada { alternativa, vezes })
+                .collect(),
+        })
+    }
+
+    /// Substitui o snapshot do resultado (recalculo explícito)
+    pub fn salvar_resultado(&self, resultado: &ResultadoSimulado) -> RusqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        Self::gravar_resultado(&tx, resultado)?;
+        tx.commit()
+    }
+
+    pub fn buscar_resultado(&self, simulado_id: i64) -> RusqliteResult<Option<ResultadoSimulado>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let json: Option<Option<String>> = conn
+            .query_row(
+                "SELECT r.resultado_json FROM resultado r
+                 JOIN simulado s ON s.id = r.simulado_id
+                 WHERE r.simulado_id = ? AND s.usuario_id = ?",
+                params![simulado_id, usuario_id],
+                |row| row.get(0),
+            )
+            .optional()?;
         
+        match json.flatten() {
+            Some(json) => serde_json::from_str(&json)
+                .map(Some)
+                .map_err(|e| Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))),
+            None => Ok(None),
+        }
+    }
+
+    fn gravar_resultado(conn: &Connection, resultado: &ResultadoSimulado) -> RusqliteResult<()> {
+        let resultado_json = serde_json::to_string(resultado)
+            .map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))?;
+        
+        conn.execute(
+            "INSERT OR REPLACE INTO resultado (
+                simulado_id, total_questoes, acertos, erros, brancos, pontuacao,
+                resultado_json, calculado_em
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                resultado.simulado_id,
+                resultado.total_questoes,
+                resultado.acertos,
+                resultado.erros,
+                resultado.brancos,
+                resultado.pontuacao,
+                &resultado_json,
+                chrono::Utc::now(),
+            ],
+        )?;
+        
+        // Linhas já gravadas por `registrar_resposta` mantêm o horário da marcação
+        let agora = chrono::Utc::now();
+        let mut stmt = conn.prepare(
+            "INSERT INTO resposta (simulado_id, questao_id, alternativa_marcada, correta, marcada_em)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT (simulado_id, questao_id) DO UPDATE SET
+                correta = excluded.correta,
+                marcada_em = CASE
+                    WHEN resposta.alternativa_marcada IS excluded.alternativa_marcada THEN resposta.marcada_em
+                    ELSE excluded.marcada_em
+                END,
+                alternativa_marcada = excluded.alternativa_marcada"
+        )?;
+        for detalhe in &resultado.detalhes {
+            stmt.execute(params![
+                resultado.simulado_id,
+                &detalhe.questao_id,
+                &detalhe.resposta_usuario,
+                detalhe.acertou,
+                detalhe.resposta_usuario.as_ref().map(|_| agora),
+            ])?;
+        }
+        Ok(())
+    }
+
+    fn salvar_em(conn: &Connection, simulado: &Simulado) -> RusqliteResult<i64> {
         let estado = simulado.estado()
             .map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))?;
         let estado_json = serde_json::to_string(&estado)
             .map_err(|e| Error::ToSqlConversionFailure(Box::new(e)))?;
 
         if simulado.id == 0 {
+            // Um simulado novo pertence ao perfil ativo; atualizações não mudam o dono
             conn.execute(
                 "INSERT INTO simulado (
                     vestibular, ano, prova_id, tempo_limite,
-                    iniciado_em, finalizado_em, estado_json
-                ) VALUES (?, ?, ?, ?, ?, ?, ?)",
+                    iniciado_em, finalizado_em, estado_json, usuario_id
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
                 params![
                     &simulado.vestibular,
                     simulado.ano,
@@ -35,6 +213,7 @@ impl SimuladoRepository {
                     &simulado.iniciado_em,
                     &simulado.finalizado_em,
                     &estado_json,
+                    perfil_repository::ativo_em(conn)?,
                 ],
             )?;
             Ok(conn.last_insert_rowid())
@@ -59,38 +238,180 @@ impl SimuladoRepository {
         }
     }
 
+    /// Simulado do perfil ativo fora da lixeira
     pub fn buscar_por_id(&self, id: i64) -> RusqliteResult<Option<Simulado>> {
         let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        conn
+            .query_row(
+                "SELECT * FROM simulado WHERE id = ? AND usuario_id = ? AND excluido_em IS NULL",
+                params![id, usuario_id],
+                Simulado::from_row,
+            )
+            .optional()
+    }
+
+    pub fn buscar_na_lixeira(&self, id: i64) -> RusqliteResult<Option<Simulado>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
         conn
             .query_row(
-                "SELECT * FROM simulado WHERE id = ?",
-                [id],
+                "SELECT * FROM simulado WHERE id = ? AND usuario_id = ? AND excluido_em IS NOT NULL",
+                params![id, usuario_id],
                 Simulado::from_row,
             )
             .optional()
     }
 
+    /// Move para a lixeira (`Some`) ou restaura (`None`)
+    pub fn marcar_excluido(&self, id: i64, excluido_em: Option<chrono::DateTime<chrono::Utc>>) -> RusqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        tx.execute(
+            "UPDATE simulado SET excluido_em = ? WHERE id = ?",
+            params![excluido_em, id],
+        )?;
+        match excluido_em {
+            Some(_) => CadernoRepository::remover_simulado_em(&tx, id, false)?,
+            None => CadernoRepository::restaurar_simulado_em(&tx, id)?,
+        }
+        
+        tx.commit()
+    }
+
+    pub fn listar_lixeira(&self) -> RusqliteResult<Vec<Simulado>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM simulado
+             WHERE usuario_id = ? AND excluido_em IS NOT NULL
+             ORDER BY excluido_em DESC"
+        )?;
+        let simulados = stmt
+            .query_map([usuario_id], Simulado::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(simulados)
+    }
+
+    /// Apaga o simulado e tudo que depende dele: respostas, resultado e vínculo com
+    /// edição. No caderno de erros sai só a parte dele: as entradas continuam com os
+    /// erros de outros simulados. Os cartões de revisão são por questão e permanecem.
+    pub fn excluir_definitivamente(&self, id: i64) -> RusqliteResult<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        Self::excluir_em(&tx, id)?;
+        tx.commit()
+    }
+
+    fn excluir_em(conn: &Connection, id: i64) -> RusqliteResult<()> {
+        CadernoRepository::remover_simulado_em(conn, id, true)?;
+        conn.execute("DELETE FROM resposta WHERE simulado_id = ?", [id])?;
+        conn.execute("DELETE FROM resultado WHERE simulado_id = ?", [id])?;
+        conn.execute("DELETE FROM edicao_simulado WHERE simulado_id = ?", [id])?;
+        conn.execute("DELETE FROM simulado WHERE id = ?", [id])?;
+        Ok(())
+    }
+
+    /// Exclui definitivamente o que está na lixeira desde antes de `limite`,
+    /// em todos os perfis. Retorna os IDs apagados.
+    pub fn purgar_lixeira(&self, limite: chrono::DateTime<chrono::Utc>) -> RusqliteResult<Vec<i64>> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        
+        let ids = tx
+            .prepare("SELECT id FROM simulado WHERE excluido_em IS NOT NULL AND excluido_em < ?")?
+            .query_map([limite], |row| row.get(0))?
+            .collect::<Result<Vec<i64>, _>>()?;
+        for id in &ids {
+            Self::excluir_em(&tx, *id)?;
+        }
+        
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    pub fn dias_lixeira(&self) -> RusqliteResult<u32> {
+        let conn = self.conn.lock().unwrap();
+        configuracao::ler_ou(&conn, CHAVE_DIAS_LIXEIRA, DIAS_LIXEIRA_PADRAO)
+    }
+
+    pub fn salvar_dias_lixeira(&self, dias: u32) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        configuracao::gravar(&conn, CHAVE_DIAS_LIXEIRA, &dias.to_string())
+    }
+
+    pub fn politica_recuperacao(&self) -> RusqliteResult<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        configuracao::ler(&conn, CHAVE_POLITICA_RECUPERACAO)
+    }
+
+    pub fn salvar_politica_recuperacao(&self, politica: &str) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        configuracao::gravar(&conn, CHAVE_POLITICA_RECUPERACAO, politica)
+    }
+
     pub fn listar_por_vestibular(&self, vestibular: &str) -> RusqliteResult<Vec<Simulado>> {
         let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
         let mut stmt = conn.prepare(
             "SELECT * FROM simulado
-             WHERE vestibular = ?
+             WHERE vestibular = ? AND usuario_id = ? AND excluido_em IS NULL
              ORDER BY iniciado_em DESC"
         )?;
 
         let simulados = stmt
-            .query_map([vestibular], Simulado::from_row)?
+            .query_map(params![vestibular, usuario_id], Simulado::from_row)?
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(simulados)
     }
 
+    /// Simulados do perfil ativo fora da lixeira
     pub fn listar_todos(&self) -> RusqliteResult<Vec<Simulado>> {
     let conn = self.conn.lock().unwrap();
-    let mut stmt = conn.prepare("SELECT * FROM simulado ORDER BY iniciado_em DESC")?;
+    let usuario_id = perfil_repository::ativo_em(&conn)?;
+    let mut stmt = conn.prepare(
+        "SELECT * FROM simulado WHERE usuario_id = ? AND excluido_em IS NULL ORDER BY iniciado_em DESC"
+    )?;
     let simulados = stmt
-        .query_map([], Simulado::from_row)?
+        .query_map([usuario_id], Simulado::from_row)?
         .collect::<Result<Vec<_>, _>>()?;
     Ok(simulados)
 }
+
+    /// Simulados do perfil ativo fora da lixeira, com a edição e o dia a que
+    /// pertencem, para o histórico agrupar os dias de uma mesma edição
+    pub fn listar_com_edicao(&self) -> RusqliteResult<Vec<(Simulado, Option<(i64, u8)>)>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let mut stmt = conn.prepare(
+            "SELECT s.*, es.edicao_id, es.dia
+             FROM simulado s
+             LEFT JOIN edicao_simulado es ON es.simulado_id = s.id
+             WHERE s.usuario_id = ? AND s.excluido_em IS NULL
+             ORDER BY s.iniciado_em DESC"
+        )?;
+        let simulados = stmt
+            .query_map([usuario_id], |row| {
+                let vinculo = match (row.get("edicao_id")?, row.get("dia")?) {
+                    (Some(edicao_id), Some(dia)) => Some((edicao_id, dia)),
+                    _ => None,
+                };
+                Ok((Simulado::from_row(row)?, vinculo))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(simulados)
+    }
+
+    /// Simulados fora da lixeira de todos os perfis, para pausar ou recuperar
+    /// os que ficaram em andamento
+    pub fn listar_de_todos_os_perfis(&self) -> RusqliteResult<Vec<Simulado>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT * FROM simulado WHERE excluido_em IS NULL ORDER BY iniciado_em DESC")?;
+        let simulados = stmt
+            .query_map([], Simulado::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(simulados)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,131 @@
+use rusqlite::{Connection, OptionalExtension as _, Result as RusqliteResult, params};
+use std::sync::{Arc, Mutex};
+use chrono::NaiveDate;
+use crate::domain::repeticao::{self, CartaoRevisao, FACILIDADE_INICIAL};
+use crate::domain::resultado::ResultadoSimulado;
+use crate::persistence::perfil_repository;
+
+pub struct RevisaoRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl RevisaoRepository {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Cria o cartão da questão no perfil ativo, para revisão a partir de `hoje`, se ainda não existir
+    pub fn adicionar(&self, prova_id: &str, questao_id: &str, area_id: &str, hoje: NaiveDate) -> RusqliteResult<bool> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let inseridas = conn.execute(
+            "INSERT OR IGNORE INTO revisao_questao (
+                usuario_id, prova_id, questao_id, area_id, repeticoes, intervalo_dias, facilidade, proxima_revisao
+            ) VALUES (?, ?, ?, ?, 0, 0, ?, ?)",
+            params![usuario_id, prova_id, questao_id, area_id, FACILIDADE_INICIAL, hoje],
+        )?;
+        Ok(inseridas > 0)
+    }
+
+    /// Cada questão respondida no simulado conta como uma revisão do seu cartão
+    /// (criado na hora, se preciso). Questões em branco não são tentativas e ficam de fora.
+    /// Chamado dentro da transação de finalização.
+    pub fn registrar_resultado_em(conn: &Connection, prova_id: &str, resultado: &ResultadoSimulado, hoje: NaiveDate) -> RusqliteResult<()> {
+        let usuario_id = perfil_repository::dono_do_simulado_em(conn, resultado.simulado_id)?;
+        for detalhe in &resultado.detalhes {
+            if detalhe.resposta_usuario.is_none() {
+                continue;
+            }
+            let existente = Self::buscar_em(conn, usuario_id, prova_id, &detalhe.questao_id)?;
+            let mut cartao = existente.unwrap_or_else(|| CartaoRevisao {
+                id: 0,
+                usuario_id,
+                prova_id: prova_id.to_string(),
+                questao_id: detalhe.questao_id.clone(),
+                area_id: detalhe.area_id.clone(),
+                repeticoes: 0,
+                intervalo_dias: 0,
+                facilidade: FACILIDADE_INICIAL,
+                proxima_revisao: hoje,
+                ultima_revisao: None,
+                ultima_nota: None,
+            });
+            let nota = if detalhe.acertou { repeticao::NOTA_ACERTO_SIMULADO } else { repeticao::NOTA_ERRO_SIMULADO };
+            cartao.revisar(nota, hoje);
+            Self::salvar_em(conn, &cartao)?;
+        }
+        Ok(())
+    }
+
+    fn buscar_em(conn: &Connection, usuario_id: i64, prova_id: &str, questao_id: &str) -> RusqliteResult<Option<CartaoRevisao>> {
+        conn
+            .query_row(
+                "SELECT * FROM revisao_questao WHERE usuario_id = ? AND prova_id = ? AND questao_id = ?",
+                params![usuario_id, prova_id, questao_id],
+                CartaoRevisao::from_row,
+            )
+            .optional()
+    }
+
+    fn salvar_em(conn: &Connection, cartao: &CartaoRevisao) -> RusqliteResult<()> {
+        conn.execute(
+            "INSERT INTO revisao_questao (
+                usuario_id, prova_id, questao_id, area_id, repeticoes, intervalo_dias, facilidade,
+                proxima_revisao, ultima_revisao, ultima_nota
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (usuario_id, prova_id, questao_id) DO UPDATE SET
+                repeticoes = excluded.repeticoes,
+                intervalo_dias = excluded.intervalo_dias,
+                facilidade = excluded.facilidade,
+                proxima_revisao = excluded.proxima_revisao,
+                ultima_revisao = excluded.ultima_revisao,
+                ultima_nota = excluded.ultima_nota",
+            params![
+                cartao.usuario_id,
+                &cartao.prova_id,
+                &cartao.questao_id,
+                &cartao.area_id,
+                cartao.repeticoes,
+                cartao.intervalo_dias,
+                cartao.facilidade,
+                cartao.proxima_revisao,
+                &cartao.ultima_revisao,
+                cartao.ultima_nota,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn salvar(&self, cartao: &CartaoRevisao) -> RusqliteResult<()> {
+        let conn = self.conn.lock().unwrap();
+        Self::salvar_em(&conn, cartao)
+    }
+
+    /// Cartão do perfil ativo
+    pub fn buscar_por_id(&self, id: i64) -> RusqliteResult<Option<CartaoRevisao>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        conn
+            .query_row(
+                "SELECT * FROM revisao_questao WHERE id = ? AND usuario_id = ?",
+                params![id, usuario_id],
+                CartaoRevisao::from_row,
+            )
+            .optional()
+    }
+
+    /// Cartões do perfil ativo com revisão marcada até `hoje`, os mais atrasados primeiro
+    pub fn listar_pendentes(&self, hoje: NaiveDate) -> RusqliteResult<Vec<CartaoRevisao>> {
+        let conn = self.conn.lock().unwrap();
+        let usuario_id = perfil_repository::ativo_em(&conn)?;
+        let mut stmt = conn.prepare(
+            "SELECT * FROM revisao_questao
+             WHERE usuario_id = ? AND proxima_revisao <= ?
+             ORDER BY proxima_revisao, facilidade"
+        )?;
+        let cartoes = stmt
+            .query_map(params![usuario_id, hoje], CartaoRevisao::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(cartoes)
+    }
+}
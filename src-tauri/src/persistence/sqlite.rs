@@ -1,71 +1,81 @@
-use rusqlite::{Connection, Result as RusqliteResult};
-use std::path::Path;
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use crate::persistence::migracoes::{self, MIGRACOES};
 
-pub fn connect(db_path: &Path) -> RusqliteResult<Connection> {
+#[derive(Debug, thiserror::Error)]
+pub enum BancoError {
+    #[error("Erro do SQLite: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Erro ao acessar {0}: {1}")]
+    Arquivo(PathBuf, #[source] std::io::Error),
+    #[error("O banco está na versão {banco}, mais nova que a suportada por este app ({suportada}). Atualize o aplicativo")]
+    VersaoMaisNova { banco: u32, suportada: u32 },
+    #[error("Falha na migração {versao} ({descricao}): {source}")]
+    Migracao {
+        versao: u32,
+        descricao: &'static str,
+        #[source]
+        source: rusqlite::Error,
+    },
+}
+
+/// Abre o banco e o leva até a versão de esquema atual.
+/// Antes de migrar um banco com dados, guarda uma cópia do arquivo ao lado dele.
+pub fn connect(db_path: &Path) -> Result<Connection, BancoError> {
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent)
-            .map_err(|e| rusqlite::Error::ExecuteReturnedResults)?;
+            .map_err(|e| BancoError::Arquivo(parent.to_path_buf(), e))?;
     }
 
-    let conn = Connection::open(db_path)?;
-
-    conn.execute_batch(
-        "
-        CREATE TABLE IF NOT EXISTS usuario (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            nome TEXT,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS simulado (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            vestibular TEXT NOT NULL,
-            ano INTEGER NOT NULL,
-            prova_id TEXT NOT NULL,
-            tempo_limite INTEGER NOT NULL,
-            iniciado_em DATETIME,
-            finalizado_em DATETIME,
-            estado_json TEXT NOT NULL
-        );
-
-        CREATE TABLE IF NOT EXISTS resposta (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            simulado_id INTEGER NOT NULL,
-            questao_id TEXT NOT NULL,
-            alternativa_marcada TEXT,
-            correta BOOLEAN,
-            FOREIGN KEY (simulado_id) REFERENCES simulado(id)
-        );
+    let mut conn = Connection::open(db_path)?;
+    migrar(&mut conn, db_path)?;
+    Ok(conn)
+}
 
-        CREATE TABLE IF NOT EXISTS resultado (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            simulado_id INTEGER NOT NULL UNIQUE,
-            total_questoes INTEGER,
-            acertos INTEGER,
-            erros INTEGER,
-            pontuacao REAL,
-            FOREIGN KEY (simulado_id) REFERENCES simulado(id)
-        );
+pub fn versao_banco(conn: &Connection) -> rusqlite::Result<u32> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+}
 
-        CREATE TABLE IF NOT EXISTS anotacao (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            titulo TEXT,
-            conteudo TEXT NOT NULL,
-            criada_em DATETIME DEFAULT CURRENT_TIMESTAMP
-        );
+/// Leva o banco aberto até a versão atual (também usado após restaurar um backup)
+pub fn migrar(conn: &mut Connection, db_path: &Path) -> Result<(), BancoError> {
+    let versao = versao_banco(conn)?;
+    let suportada = migracoes::versao_atual();
+    
+    if versao > suportada {
+        return Err(BancoError::VersaoMaisNova { banco: versao, suportada });
+    }
+    if versao == suportada {
+        return Ok(());
+    }
 
-        CREATE TABLE IF NOT EXISTS agenda_evento (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            titulo TEXT NOT NULL,
-            descricao TEXT,
-            data_inicio DATETIME NOT NULL,
-            data_fim DATETIME
-        );
+    let tem_dados: bool = conn.query_row("SELECT COUNT(*) > 0 FROM sqlite_master", [], |row| row.get(0))?;
+    if tem_dados {
+        let backup = caminho_backup(db_path, versao);
+        std::fs::copy(db_path, &backup)
+            .map_err(|e| BancoError::Arquivo(backup.clone(), e))?;
+        println!("💾 Backup do banco antes da migração: {:?}", backup);
+    }
 
-        CREATE INDEX IF NOT EXISTS idx_resposta_simulado ON resposta(simulado_id);
-        CREATE INDEX IF NOT EXISTS idx_simulado_vestibular ON simulado(vestibular);
-        "
-    )?;
+    for migracao in MIGRACOES.iter().filter(|m| m.versao > versao) {
+        let erro_migracao = |source| BancoError::Migracao {
+            versao: migracao.versao,
+            descricao: migracao.descricao,
+            source,
+        };
+        
+        let tx = conn.transaction().map_err(erro_migracao)?;
+        (migracao.aplicar)(&tx).map_err(erro_migracao)?;
+        tx.pragma_update(None, "user_version", migracao.versao).map_err(erro_migracao)?;
+        tx.commit().map_err(erro_migracao)?;
+        
+        println!("🧱 Migração {} aplicada: {}", migracao.versao, migracao.descricao);
+    }
+    Ok(())
+}
 
-    Ok(conn)
+/// `simulados.db` => `simulados.db.v3-20240310T140500.bak`
+fn caminho_backup(db_path: &Path, versao: u32) -> PathBuf {
+    let mut nome = db_path.file_name().unwrap_or_default().to_os_string();
+    nome.push(format!(".v{}-{}.bak", versao, chrono::Local::now().format("%Y%m%dT%H%M%S")));
+    db_path.with_file_name(nome)
 }
@@ -0,0 +1,186 @@
+use crate::persistence::migracoes;
+use crate::persistence::sqlite;
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Snapshots diários mantidos na pasta de backups
+const SNAPSHOTS_MANTIDOS: usize = 7;
+const PREFIXO_SNAPSHOT: &str = "simulados-";
+/// Cópias do banco feitas antes de cada restauração
+const COPIAS_RESTAURACAO_MANTIDAS: usize = 3;
+const PREFIXO_RESTAURACAO: &str = "antes-da-restauracao-";
+
+#[derive(serde::Serialize)]
+pub struct BackupDisponivel {
+    pub caminho: String,
+    pub nome: String,
+    pub tamanho_bytes: u64,
+}
+
+/// Cópias do banco com a API de backup online do SQLite: a cópia é consistente
+/// mesmo com o app em uso, sem depender de copiar o arquivo.
+pub struct BackupService {
+    conn: Arc<Mutex<Connection>>,
+    db_path: PathBuf,
+    backups_dir: PathBuf,
+}
+
+impl BackupService {
+    pub fn new(conn: Arc<Mutex<Connection>>, db_path: PathBuf, backups_dir: PathBuf) -> Self {
+        Self { conn, db_path, backups_dir }
+    }
+
+    /// Grava uma cópia consistente do banco em `destino`
+    pub fn exportar(&self, destino: &Path) -> Result<()> {
+        // Grava ao lado e renomeia, para não deixar meio arquivo se algo falhar
+        let mut temporario = destino.as_os_str().to_os_string();
+        temporario.push(".tmp");
+        let temporario = PathBuf::from(temporario);
+        
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.backup(DatabaseName::Main, &temporario, None)?;
+        }
+        fs::rename(&temporario, destino)
+            .map_err(|e| anyhow!("Erro ao gravar backup em {:?}: {}", destino, e))?;
+        
+        println!("💾 Backup exportado para {:?}", destino);
+        Ok(())
+    }
+
+    /// Confere se o arquivo é um banco deste app, íntegro e de versão suportada
+    fn validar(origem: &Path) -> Result<u32> {
+        let conn = Connection::open_with_flags(origem, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| anyhow!("{:?} não pôde ser aberto como banco SQLite: {}", origem, e))?;
+        
+        let integridade: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))
+            .map_err(|e| anyhow!("{:?} não é um banco SQLite válido: {}", origem, e))?;
+        if integridade != "ok" {
+            return Err(anyhow!("Backup corrompido: {}", integridade));
+        }
+        
+        let tem_simulados: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'simulado'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !tem_simulados {
+            return Err(anyhow!("{:?} não é um backup do histórico de simulados", origem));
+        }
+        
+        let versao = sqlite::versao_banco(&conn)?;
+        if versao > migracoes::versao_atual() {
+            return Err(anyhow!(
+                "Backup feito por uma versão mais nova do app (esquema {}, suportado até {})",
+                versao, migracoes::versao_atual()
+            ));
+        }
+        Ok(versao)
+    }
+
+    /// Substitui o banco atual pelo backup, depois de validá-lo. O estado atual
+    /// é guardado antes (as últimas 3 cópias ficam em `backups/`), para a restauração
+    /// poder ser desfeita. Se o backup não puder ser migrado, o banco anterior volta.
+    pub fn restaurar(&self, origem: &Path) -> Result<()> {
+        let versao = Self::validar(origem)?;
+        
+        fs::create_dir_all(&self.backups_dir)
+            .map_err(|e| anyhow!("Erro ao criar {:?}: {}", self.backups_dir, e))?;
+        let antes = self.backups_dir.join(format!("{}{}.db", PREFIXO_RESTAURACAO, Local::now().format("%Y%m%dT%H%M%S")));
+        self.exportar(&antes)?;
+        
+        {
+            let mut conn = self.conn.lock().unwrap();
+            conn.restore(DatabaseName::Main, origem, None::<fn(rusqlite::backup::Progress)>)?;
+            if let Err(erro) = sqlite::migrar(&mut conn, &self.db_path) {
+                conn.restore(DatabaseName::Main, &antes, None::<fn(rusqlite::backup::Progress)>)
+                    .map_err(|e| anyhow!(
+                        "Falha ao migrar o backup ({}) e ao voltar o banco anterior ({}); ele está em {:?}",
+                        erro, e, antes
+                    ))?;
+                return Err(anyhow!("Backup não pôde ser atualizado para o esquema atual: {}", erro));
+            }
+        }
+        
+        println!("♻️ Banco restaurado de {:?} (esquema {}); estado anterior em {:?}", origem, versao, antes);
+        self.rotacionar(PREFIXO_RESTAURACAO, COPIAS_RESTAURACAO_MANTIDAS)?;
+        Ok(())
+    }
+
+    /// Faz o snapshot do dia, se ainda não existir, e apaga os mais antigos.
+    /// Chamado na inicialização. Retorna o caminho do snapshot criado.
+    pub fn snapshot_diario(&self) -> Result<Option<PathBuf>> {
+        fs::create_dir_all(&self.backups_dir)
+            .map_err(|e| anyhow!("Erro ao criar {:?}: {}", self.backups_dir, e))?;
+        
+        let caminho = self.backups_dir.join(format!("{}{}.db", PREFIXO_SNAPSHOT, Local::now().format("%Y-%m-%d")));
+        let criado = if caminho.exists() {
+            None
+        } else {
+            self.exportar(&caminho)?;
+            Some(caminho)
+        };
+        
+        self.rotacionar(PREFIXO_SNAPSHOT, SNAPSHOTS_MANTIDOS)?;
+        Ok(criado)
+    }
+
+    /// Apaga as cópias com o prefixo além das `manter` mais recentes.
+    /// Os nomes trazem a data ISO: a ordem alfabética é a cronológica.
+    fn rotacionar(&self, prefixo: &str, manter: usize) -> Result<()> {
+        let mut copias: Vec<PathBuf> = self.listar_com_prefixo(prefixo)?;
+        copias.sort();
+        let excedentes = copias.len().saturating_sub(manter);
+        for antiga in &copias[..excedentes] {
+            fs::remove_file(antiga)
+                .map_err(|e| anyhow!("Erro ao apagar backup {:?}: {}", antiga, e))?;
+            println!("🧹 Backup antigo removido: {:?}", antiga);
+        }
+        Ok(())
+    }
+
+    fn listar_com_prefixo(&self, prefixo: &str) -> Result<Vec<PathBuf>> {
+        if !self.backups_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut copias = Vec::new();
+        for entrada in fs::read_dir(&self.backups_dir)? {
+            let caminho = entrada?.path();
+            let nome = caminho.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            if nome.starts_with(prefixo) && nome.ends_with(".db") {
+                copias.push(caminho);
+            }
+        }
+        Ok(copias)
+    }
+
+    /// Backups da pasta do app (snapshots diários e cópias feitas antes de restaurações),
+    /// do mais recente para o mais antigo
+    pub fn listar_backups(&self) -> Result<Vec<BackupDisponivel>> {
+        if !self.backups_dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut backups = Vec::new();
+        for entrada in fs::read_dir(&self.backups_dir)? {
+            let entrada = entrada?;
+            let caminho = entrada.path();
+            if caminho.extension().and_then(|e| e.to_str()) != Some("db") {
+                continue;
+            }
+            backups.push((
+                entrada.metadata()?.modified()?,
+                BackupDisponivel {
+                    nome: entrada.file_name().to_string_lossy().into_owned(),
+                    caminho: caminho.to_string_lossy().into_owned(),
+                    tamanho_bytes: entrada.metadata()?.len(),
+                },
+            ));
+        }
+        backups.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(backups.into_iter().map(|(_, backup)| backup).collect())
+    }
+}
@@ -0,0 +1,331 @@
+//! Boletim de desempenho para impressão, gerado a partir do `ResultadoSimulado`.
+//! HTML autocontido (CSS embutido, sem recursos externos) e PDF montado à mão,
+//! com as fontes padrão do PDF, sem depender de navegador.
+
+use crate::domain::resultado::{DetalheQuestao, ResultadoSimulado};
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatoBoletim {
+    Html,
+    Pdf,
+}
+
+impl FormatoBoletim {
+    /// Deduz o formato pela extensão do arquivo (`.pdf`; qualquer outra vira HTML)
+    pub fn pela_extensao(caminho: &Path) -> Self {
+        match caminho.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("pdf") => Self::Pdf,
+            _ => Self::Html,
+        }
+    }
+}
+
+/// Tudo o que vai no boletim além do próprio resultado
+pub struct DadosBoletim<'a> {
+    pub prova_id: &'a str,
+    pub vestibular: &'a str,
+    pub ano: i32,
+    pub data: Option<DateTime<Utc>>,
+    pub tempo_segundos: Option<u32>,
+    pub resultado: &'a ResultadoSimulado,
+}
+
+impl DadosBoletim<'_> {
+    fn data_formatada(&self) -> String {
+        self.data
+            .map(|dt| dt.with_timezone(&Local).format("%d/%m/%Y %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    fn tempo_formatado(&self) -> String {
+        match self.tempo_segundos {
+            Some(s) if s > 0 => format!("{:02}:{:02}:{:02}", s / 3600, (s % 3600) / 60, s % 60),
+            _ => "não registrado".to_string(),
+        }
+    }
+
+    fn titulo(&self) -> String {
+        format!("Boletim - {} {} ({})", self.vestibular.to_uppercase(), self.ano, self.prova_id)
+    }
+
+    /// Linhas do resumo geral, como pares rótulo/valor
+    fn resumo(&self) -> Vec<(&'static str, String)> {
+        let r = self.resultado;
+        let mut linhas = vec![
+            ("Data", self.data_formatada()),
+            ("Tempo utilizado", self.tempo_formatado()),
+            ("Pontuação", format!("{:.1}", r.pontuacao)),
+            ("Acertos", format!("{} de {}", r.acertos, r.total_questoes)),
+            ("Erros", r.erros.to_string()),
+            ("Em branco", r.brancos.to_string()),
+        ];
+        if let Some(percentil) = r.comparativo.as_ref().and_then(|c| c.percentil) {
+            linhas.push(("Percentil", format!("{:.0}", percentil)));
+        }
+        if r.regras.as_ref().is_some_and(|a| a.eliminado) {
+            linhas.push(("Situação", "eliminado pela nota mínima".to_string()));
+        }
+        linhas
+    }
+}
+
+fn situacao(detalhe: &DetalheQuestao) -> &'static str {
+    if detalhe.acertou {
+        "Certa"
+    } else if detalhe.resposta_usuario.is_none() {
+        "Em branco"
+    } else {
+        "Errada"
+    }
+}
+
+// === HTML ===
+
+fn escapar_html(texto: &str) -> String {
+    texto
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+const ESTILO_HTML: &str = "\
+body { font-family: Helvetica, Arial, sans-serif; color: #222; margin: 2em; }
+h1 { font-size: 1.4em; margin-bottom: 0.2em; }
+h2 { font-size: 1.1em; margin-top: 1.6em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; }
+th { background: #f0f0f0; }
+td.certa { color: #1a7f37; }
+td.errada { color: #c62828; }
+td.branco { color: #777; }
+@media print { body { margin: 0; } tr { page-break-inside: avoid; } }
+";
+
+pub fn gerar_html(dados: &DadosBoletim) -> String {
+    let r = dados.resultado;
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"pt-BR\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escapar_html(&dados.titulo())));
+    html.push_str(&format!("<style>\n{}</style>\n</head>\n<body>\n", ESTILO_HTML));
+    html.push_str(&format!("<h1>{}</h1>\n", escapar_html(&dados.titulo())));
+
+    html.push_str("<table>\n");
+    for (rotulo, valor) in dados.resumo() {
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", rotulo, escapar_html(&valor)));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Desempenho por área</h2>\n<table>\n");
+    html.push_str("<tr><th>Área</th><th>Acertos</th><th>Erros</th><th>Em branco</th><th>%</th><th>Nota TRI</th></tr>\n");
+    for area in &r.areas {
+        let nota_tri = r.tri.iter()
+            .find(|t| t.area_id == area.area_id)
+            .map(|t| format!("{:.1}", t.nota))
+            .unwrap_or_else(|| "-".to_string());
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{} de {}</td><td>{}</td><td>{}</td><td>{:.1}</td><td>{}</td></tr>\n",
+            escapar_html(&area.area_id), area.acertos, area.total, area.erros, area.brancos, area.percentual, nota_tri
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Respostas</h2>\n<table>\n");
+    html.push_str("<tr><th>Nº</th><th>Área</th><th>Resposta</th><th>Gabarito</th><th>Situação</th></tr>\n");
+    for detalhe in &r.detalhes {
+        let classe = match situacao(detalhe) {
+            "Certa" => "certa",
+            "Errada" => "errada",
+            _ => "branco",
+        };
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"{}\">{}</td></tr>\n",
+            detalhe.numero,
+            escapar_html(&detalhe.area_id),
+            escapar_html(detalhe.resposta_usuario.as_deref().unwrap_or("-")),
+            escapar_html(&detalhe.gabarito),
+            classe,
+            situacao(detalhe)
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+// === PDF ===
+
+const LARGURA_PAGINA: f32 = 595.0; // A4 em pontos
+const ALTURA_PAGINA: f32 = 842.0;
+const MARGEM: f32 = 50.0;
+
+/// Texto para string literal do PDF em WinAnsiEncoding. Caracteres fora do
+/// Latin-1 (que coincide com o WinAnsi nas letras acentuadas) viram '?'.
+fn texto_pdf(texto: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(texto.len() + 2);
+    bytes.push(b'(');
+    for c in texto.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                bytes.push(b'\\');
+                bytes.push(c as u8);
+            }
+            c if (c as u32) < 0x20 => bytes.push(b' '),
+            c if (c as u32) <= 0xFF => bytes.push(c as u32 as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes.push(b')');
+    bytes
+}
+
+/// Monta o conteúdo das páginas de cima para baixo, quebrando página quando falta espaço
+struct PaginasPdf {
+    paginas: Vec<Vec<u8>>,
+    atual: Vec<u8>,
+    y: f32,
+}
+
+impl PaginasPdf {
+    fn new() -> Self {
+        Self { paginas: Vec::new(), atual: Vec::new(), y: ALTURA_PAGINA - MARGEM }
+    }
+
+    /// Desce o cursor em `altura`, abrindo nova página se não couber
+    fn linha(&mut self, altura: f32) {
+        if self.y - altura < MARGEM {
+            self.paginas.push(std::mem::take(&mut self.atual));
+            self.y = ALTURA_PAGINA - MARGEM;
+        }
+        self.y -= altura;
+    }
+
+    fn texto(&mut self, x: f32, tamanho: f32, negrito: bool, texto: &str) {
+        let fonte = if negrito { "F2" } else { "F1" };
+        self.atual.extend_from_slice(format!("BT /{} {} Tf {:.1} {:.1} Td ", fonte, tamanho, x, self.y).as_bytes());
+        self.atual.extend_from_slice(&texto_pdf(texto));
+        self.atual.extend_from_slice(b" Tj ET\n");
+    }
+
+    /// Linha de tabela: um texto por coluna, nas posições `colunas`
+    fn celulas(&mut self, colunas: &[f32], negrito: bool, textos: &[&str]) {
+        self.linha(14.0);
+        for (x, texto) in colunas.iter().zip(textos) {
+            self.texto(*x, 10.0, negrito, texto);
+        }
+    }
+
+    fn separador(&mut self) {
+        self.atual.extend_from_slice(
+            format!("0.7 G {:.1} {:.1} m {:.1} {:.1} l S 0 G\n", MARGEM, self.y - 4.0, LARGURA_PAGINA - MARGEM, self.y - 4.0).as_bytes(),
+        );
+    }
+
+    fn concluir(mut self) -> Vec<Vec<u8>> {
+        if !self.atual.is_empty() || self.paginas.is_empty() {
+            self.paginas.push(self.atual);
+        }
+        self.paginas
+    }
+}
+
+pub fn gerar_pdf(dados: &DadosBoletim) -> Vec<u8> {
+    let r = dados.resultado;
+    let mut paginas = PaginasPdf::new();
+
+    paginas.linha(18.0);
+    paginas.texto(MARGEM, 16.0, true, &dados.titulo());
+    paginas.linha(8.0);
+    for (rotulo, valor) in dados.resumo() {
+        paginas.linha(15.0);
+        paginas.texto(MARGEM, 11.0, true, rotulo);
+        paginas.texto(MARGEM + 130.0, 11.0, false, &valor);
+    }
+
+    let colunas_area = [MARGEM, MARGEM + 170.0, MARGEM + 250.0, MARGEM + 310.0, MARGEM + 380.0, MARGEM + 430.0];
+    paginas.linha(28.0);
+    paginas.texto(MARGEM, 13.0, true, "Desempenho por área");
+    paginas.linha(4.0);
+    paginas.celulas(&colunas_area, true, &["Área", "Acertos", "Erros", "Em branco", "%", "Nota TRI"]);
+    paginas.separador();
+    for area in &r.areas {
+        let nota_tri = r.tri.iter()
+            .find(|t| t.area_id == area.area_id)
+            .map(|t| format!("{:.1}", t.nota))
+            .unwrap_or_else(|| "-".to_string());
+        paginas.celulas(&colunas_area, false, &[
+            &area.area_id,
+            &format!("{} de {}", area.acertos, area.total),
+            &area.erros.to_string(),
+            &area.brancos.to_string(),
+            &format!("{:.1}", area.percentual),
+            &nota_tri,
+        ]);
+    }
+
+    let colunas_respostas = [MARGEM, MARGEM + 40.0, MARGEM + 210.0, MARGEM + 290.0, MARGEM + 370.0];
+    paginas.linha(28.0);
+    paginas.texto(MARGEM, 13.0, true, "Respostas");
+    paginas.linha(4.0);
+    paginas.celulas(&colunas_respostas, true, &["Nº", "Área", "Resposta", "Gabarito", "Situação"]);
+    paginas.separador();
+    for detalhe in &r.detalhes {
+        paginas.celulas(&colunas_respostas, false, &[
+            &detalhe.numero.to_string(),
+            &detalhe.area_id,
+            detalhe.resposta_usuario.as_deref().unwrap_or("-"),
+            &detalhe.gabarito,
+            situacao(detalhe),
+        ]);
+    }
+
+    montar_documento(paginas.concluir())
+}
+
+/// Serializa catálogo, páginas, fontes e tabela xref.
+/// Objetos: 1 catálogo, 2 árvore de páginas, 3 e 4 fontes, depois página + conteúdo para cada página.
+fn montar_documento(conteudos: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut objetos: Vec<Vec<u8>> = Vec::new();
+
+    let kids: Vec<String> = (0..conteudos.len()).map(|i| format!("{} 0 R", 5 + i * 2)).collect();
+    objetos.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objetos.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), conteudos.len()).into_bytes());
+    objetos.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec());
+    objetos.push(b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec());
+
+    for (i, conteudo) in conteudos.into_iter().enumerate() {
+        objetos.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            LARGURA_PAGINA, ALTURA_PAGINA, 6 + i * 2
+        ).into_bytes());
+
+        let mut stream = format!("<< /Length {} >>\nstream\n", conteudo.len()).into_bytes();
+        stream.extend_from_slice(&conteudo);
+        stream.extend_from_slice(b"\nendstream");
+        objetos.push(stream);
+    }
+
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objetos.len());
+    for (i, objeto) in objetos.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(objeto);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+
+    let inicio_xref = pdf.len();
+    pdf.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", objetos.len() + 1).as_bytes());
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objetos.len() + 1, inicio_xref).as_bytes(),
+    );
+    pdf
+}
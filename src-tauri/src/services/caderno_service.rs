@@ -0,0 +1,111 @@
+use crate::domain::caderno::{ConfiguracaoCaderno, EntradaCaderno, FiltroCaderno};
+use crate::domain::questao::Questao;
+use crate::persistence::caderno_repository::CadernoRepository;
+use crate::services::prova_service::ProvaService;
+use anyhow::{Result, anyhow};
+use std::path::PathBuf;
+
+/// Questão do caderno pronta para o treino
+#[derive(serde::Serialize)]
+pub struct QuestaoTreino {
+    pub entrada: EntradaCaderno,
+    pub questao: Questao,
+}
+
+/// Correção de uma resposta dada no treino do caderno
+#[derive(serde::Serialize)]
+pub struct CorrecaoTreino {
+    pub entrada_id: i64,
+    pub acertou: bool,
+    pub gabarito: String,
+    pub explicacao: Option<String>,
+    pub acertos_seguidos: u32,
+    /// A questão atingiu os acertos necessários e saiu do caderno
+    pub removida: bool,
+}
+
+pub struct CadernoService {
+    repo: CadernoRepository,
+    provas_dir: PathBuf,
+}
+
+impl CadernoService {
+    pub fn new(repo: CadernoRepository, provas_dir: PathBuf) -> Self {
+        Self { repo, provas_dir }
+    }
+
+    pub fn listar(&self, filtro: &FiltroCaderno) -> Result<Vec<EntradaCaderno>> {
+        Ok(self.repo.listar(filtro)?)
+    }
+
+    pub fn obter_configuracao(&self) -> Result<ConfiguracaoCaderno> {
+        Ok(self.repo.configuracao()?)
+    }
+
+    pub fn configurar(&self, config: ConfiguracaoCaderno) -> Result<()> {
+        if config.acertos_para_remover == 0 {
+            return Err(anyhow!("São necessários ao menos 1 acerto para remover uma questão do caderno"));
+        }
+        self.repo.salvar_configuracao(&config)?;
+        println!("📒 Caderno de erros configurado: {:?}", config);
+        Ok(())
+    }
+
+    /// Monta uma sessão de treino com as questões do caderno, começando pelas
+    /// com menos acertos seguidos e, entre elas, pelas erradas há mais tempo.
+    /// Questões cuja prova não existe mais na biblioteca são ignoradas.
+    pub fn iniciar_treino(&self, filtro: &FiltroCaderno, limite: Option<usize>) -> Result<Vec<QuestaoTreino>> {
+        let mut entradas = self.repo.listar(filtro)?;
+        entradas.sort_by_key(|e| (e.acertos_seguidos, e.errado_em));
+        
+        let prova_service = ProvaService::new(self.provas_dir.clone());
+        let mut treino = Vec::new();
+        for entrada in entradas {
+            if limite.is_some_and(|limite| treino.len() >= limite) {
+                break;
+            }
+            let questao = match prova_service.carregar(&entrada.prova_id) {
+                Ok(prova) => prova.questoes.into_iter().find(|q| q.id == entrada.questao_id),
+                Err(e) => {
+                    println!("⚠️ Prova {} do caderno não pôde ser carregada: {}", entrada.prova_id, e);
+                    None
+                }
+            };
+            if let Some(questao) = questao {
+                treino.push(QuestaoTreino { entrada, questao });
+            }
+        }
+        
+        println!("📒 Treino do caderno com {} questões", treino.len());
+        Ok(treino)
+    }
+
+    pub fn responder_treino(&self, entrada_id: i64, alternativa: String) -> Result<CorrecaoTreino> {
+        let entrada = self.repo.buscar_por_id(entrada_id)?
+            .ok_or_else(|| anyhow!("Questão {} não está no caderno de erros", entrada_id))?;
+        let questao = ProvaService::new(self.provas_dir.clone())
+            .carregar(&entrada.prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar prova: {}", e))?
+            .questoes
+            .into_iter()
+            .find(|q| q.id == entrada.questao_id)
+            .ok_or_else(|| anyhow!("Questão {} não existe mais na prova {}", entrada.questao_id, entrada.prova_id))?;
+        
+        let acertou = alternativa == questao.resposta_correta;
+        let (acertos_seguidos, removida) = if acertou {
+            (entrada.acertos_seguidos + 1, self.repo.registrar_acerto(entrada_id)?)
+        } else {
+            self.repo.registrar_erro_treino(entrada_id, &alternativa)?;
+            (0, false)
+        };
+        
+        Ok(CorrecaoTreino {
+            entrada_id,
+            acertou,
+            gabarito: questao.resposta_correta,
+            explicacao: questao.explicacao,
+            acertos_seguidos,
+            removida,
+        })
+    }
+}
@@ -0,0 +1,289 @@
+use crate::domain::edicao::Edicao;
+use crate::domain::estado::ModoSimulado;
+use crate::persistence::edicao_repository::EdicaoRepository;
+use crate::services::prova_service::ProvaService;
+use crate::domain::resultado::{percentual, ResultadoArea};
+use crate::services::simulado_service::SimuladoService;
+use anyhow::{Result, anyhow};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(serde::Serialize)]
+pub struct DiaEdicao {
+    pub dia: u8,
+    pub simulado_id: Option<i64>,
+    pub prova_id: Option<String>,
+    pub estado: Option<String>,
+    pub concluido: bool,
+    pub pontuacao: Option<f64>,
+}
+
+/// Resultado combinado de todos os dias da edição
+#[derive(serde::Serialize)]
+pub struct ResultadoEdicao {
+    pub total_questoes: usize,
+    pub acertos: usize,
+    pub erros: usize,
+    pub brancos: usize,
+    pub pontuacao: f64,
+    pub areas: Vec<ResultadoArea>,
+}
+
+#[derive(serde::Serialize)]
+pub struct EdicaoResumo {
+    pub id: i64,
+    pub vestibular: String,
+    pub ano: i32,
+    pub criada_em: Option<String>,
+    pub dias: Vec<DiaEdicao>,
+    pub completa: bool,
+    pub resultado: Option<ResultadoEdicao>,
+}
+
+/// Nota de uma área na edição, na escala 0–1000
+#[derive(serde::Serialize)]
+pub struct NotaAreaEdicao {
+    pub area_id: String,
+    pub nota: f64,
+    /// `true` quando veio da TRI; senão é o percentual de acertos × 10
+    pub estimada_por_tri: bool,
+}
+
+/// Notas da edição e os dias que ficaram de fora do cálculo
+pub struct NotasEdicao {
+    pub notas: Vec<NotaAreaEdicao>,
+    pub avisos: Vec<String>,
+}
+
+pub struct EdicaoService {
+    repo: EdicaoRepository,
+    simulados: Arc<SimuladoService>,
+    provas_dir: PathBuf,
+}
+
+impl EdicaoService {
+    pub fn new(repo: EdicaoRepository, simulados: Arc<SimuladoService>, provas_dir: PathBuf) -> Self {
+        Self { repo, simulados, provas_dir }
+    }
+
+    pub fn criar_edicao(&self, vestibular: String, ano: i32) -> Result<i64> {
+        let dias = self.dias_da_edicao(&vestibular, ano)?;
+        if dias.is_empty() {
+            return Err(anyhow!("Nenhuma prova de {} {} dividida em dias foi encontrada", vestibular, ano));
+        }
+        
+        let id = self.repo.criar(&vestibular, ano)?;
+        println!("📚 Edição {} criada: {} {} (dias {:?})", id, vestibular, ano, dias);
+        Ok(id)
+    }
+
+    /// Confere se a prova é um dos dias da edição e devolve o dia. Chamado antes de
+    /// criar o simulado, para que um vínculo inválido não deixe um simulado órfão.
+    /// Simulados de estudo não entram: a edição reproduz as condições de prova.
+    pub fn validar_vinculo(&self, edicao_id: i64, prova_id: &str, modo: ModoSimulado) -> Result<u8> {
+        if modo == ModoSimulado::Estudo {
+            return Err(anyhow!("Simulados no modo estudo não podem fazer parte de uma edição"));
+        }
+        let edicao = self.buscar_edicao(edicao_id)?;
+        
+        let prova = ProvaService::new(self.provas_dir.clone())
+            .carregar(prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar prova: {}", e))?;
+        
+        if !prova.vestibular.eq_ignore_ascii_case(&edicao.vestibular) || prova.ano as i32 != edicao.ano {
+            return Err(anyhow!("Prova {} não pertence à edição {} {}", 
+                prova_id, edicao.vestibular, edicao.ano));
+        }
+        
+        prova.dia
+            .ok_or_else(|| anyhow!("Prova {} não é dividida em dias", prova_id))
+    }
+
+    /// Vincula um simulado ao dia correspondente da edição, conforme o `dia` da prova.
+    /// Um simulado já vinculado a outra edição é recusado.
+    pub fn vincular_simulado(&self, edicao_id: i64, simulado_id: i64) -> Result<()> {
+        let simulado = self.simulados.buscar_simulado(simulado_id)?;
+        let dia = self.validar_vinculo(edicao_id, &simulado.prova_id, simulado.estado()?.modo)?;
+        
+        if let Some(outra) = self.repo.edicao_do_simulado(simulado_id)? {
+            if outra != edicao_id {
+                return Err(anyhow!("Simulado {} já pertence à edição {}", simulado_id, outra));
+            }
+        }
+        
+        self.repo.vincular(edicao_id, dia, simulado_id)?;
+        println!("🔗 Simulado {} vinculado ao dia {} da edição {}", simulado_id, dia, edicao_id);
+        Ok(())
+    }
+
+    pub fn obter_edicao(&self, edicao_id: i64) -> Result<EdicaoResumo> {
+        let edicao = self.buscar_edicao(edicao_id)?;
+        self.resumir(edicao)
+    }
+
+    pub fn listar_edicoes(&self) -> Result<Vec<EdicaoResumo>> {
+        self.repo.listar_todas()?
+            .into_iter()
+            .map(|edicao| self.resumir(edicao))
+            .collect()
+    }
+
+    /// Nota por área dos dias já concluídos: a estimativa TRI quando a prova traz
+    /// os parâmetros dos itens, senão o percentual de acertos levado para 0–1000.
+    /// Simulados de estudo vinculados antes da restrição ficam de fora, e um dia com
+    /// estado ilegível vira aviso em vez de impedir a simulação.
+    pub fn notas_por_area(&self, edicao_id: i64) -> Result<NotasEdicao> {
+        self.buscar_edicao(edicao_id)?;
+        
+        let mut notas = Vec::new();
+        let mut avisos = Vec::new();
+        for vinculo in self.repo.listar_vinculos(edicao_id)? {
+            let simulado = self.simulados.buscar_simulado(vinculo.simulado_id)?;
+            let estado = match simulado.estado() {
+                Ok(estado) => estado,
+                Err(e) => {
+                    println!("⚠️ Estado ilegível no simulado {}: {}", simulado.id, e);
+                    avisos.push(format!("Dia {} ignorado: o simulado {} está corrompido ({})", vinculo.dia, simulado.id, e));
+                    continue;
+                }
+            };
+            if !estado.estado.esta_finalizado() || estado.modo == ModoSimulado::Estudo {
+                continue;
+            }
+            let resultado = self.simulados.obter_resultado(simulado.id)?;
+            for area in &resultado.areas {
+                let tri = resultado.tri.iter().find(|t| t.area_id == area.area_id);
+                notas.push(NotaAreaEdicao {
+                    area_id: area.area_id.clone(),
+                    nota: tri.map_or(area.percentual * 10.0, |t| t.nota),
+                    estimada_por_tri: tri.is_some(),
+                });
+            }
+        }
+        Ok(NotasEdicao { notas, avisos })
+    }
+
+    fn buscar_edicao(&self, edicao_id: i64) -> Result<Edicao> {
+        self.repo.buscar_por_id(edicao_id)?
+            .ok_or_else(|| anyhow!("Edição {} não encontrada", edicao_id))
+    }
+
+    /// Dias disponíveis na biblioteca de provas para o vestibular e ano
+    fn dias_da_edicao(&self, vestibular: &str, ano: i32) -> Result<BTreeSet<u8>> {
+        let prova_service = ProvaService::new(self.provas_dir.clone());
+        let mut dias = BTreeSet::new();
+        
+        for prova_id in prova_service.listar_ids()? {
+            let Ok(prova) = prova_service.carregar(&prova_id) else {
+                continue;
+            };
+            if prova.vestibular.eq_ignore_ascii_case(vestibular) && prova.ano as i32 == ano {
+                if let Some(dia) = prova.dia {
+                    dias.insert(dia);
+                }
+            }
+        }
+        Ok(dias)
+    }
+
+    fn resumir(&self, edicao: Edicao) -> Result<EdicaoResumo> {
+        let vinculos = self.repo.listar_vinculos(edicao.id)?;
+        
+        let mut dias_esperados = self.dias_da_edicao(&edicao.vestibular, edicao.ano)?;
+        dias_esperados.extend(vinculos.iter().map(|v| v.dia));
+        
+        let mut dias = Vec::new();
+        let mut areas: Vec<ResultadoArea> = Vec::new();
+        
+        for dia in dias_esperados {
+            let mut vinculado = None;
+            if let Some(vinculo) = vinculos.iter().find(|v| v.dia == dia) {
+                let simulado = self.simulados.buscar_simulado(vinculo.simulado_id)?;
+                let estado = simulado.estado()?;
+                // Simulado de estudo vinculado antes da restrição: o dia conta como não feito
+                if estado.modo != ModoSimulado::Estudo {
+                    vinculado = Some((simulado, estado));
+                }
+            }
+            let Some((simulado, estado)) = vinculado else {
+                dias.push(DiaEdicao {
+                    dia,
+                    simulado_id: None,
+                    prova_id: None,
+                    estado: None,
+                    concluido: false,
+                    pontuacao: None,
+                });
+                continue;
+            };
+            
+            let concluido = estado.estado.esta_finalizado();
+            
+            let mut pontuacao = None;
+            if concluido {
+                let resultado = self.simulados.obter_resultado(simulado.id)?;
+                for area in resultado.areas {
+                    match areas.iter_mut().find(|a| a.area_id == area.area_id) {
+                        Some(existente) => somar_area(existente, &area),
+                        None => areas.push(area),
+                    }
+                }
+                pontuacao = Some(resultado.pontuacao);
+            }
+            
+            dias.push(DiaEdicao {
+                dia,
+                simulado_id: Some(simulado.id),
+                prova_id: Some(simulado.prova_id),
+                estado: Some(format!("{:?}", estado.estado)),
+                concluido,
+                pontuacao,
+            });
+        }
+        
+        let completa = !dias.is_empty() && dias.iter().all(|d| d.concluido);
+        let resultado = completa.then(|| Self::combinar(areas));
+        
+        Ok(EdicaoResumo {
+            id: edicao.id,
+            vestibular: edicao.vestibular,
+            ano: edicao.ano,
+            criada_em: edicao.criada_em.map(|dt| dt.to_rfc3339()),
+            dias,
+            completa,
+            resultado,
+        })
+    }
+
+    fn combinar(mut areas: Vec<ResultadoArea>) -> ResultadoEdicao {
+        for area in &mut areas {
+            area.percentual = percentual(area.acertos, area.total);
+        }
+        let total: usize = areas.iter().map(|a| a.total).sum();
+        let acertos: usize = areas.iter().map(|a| a.acertos).sum();
+        let erros: usize = areas.iter().map(|a| a.erros).sum();
+        let brancos: usize = areas.iter().map(|a| a.brancos).sum();
+        
+        ResultadoEdicao {
+            total_questoes: total,
+            acertos,
+            erros,
+            brancos,
+            pontuacao: percentual(acertos, total),
+            areas,
+        }
+    }
+}
+
+/// Soma os números de uma área que aparece em mais de um dia
+fn somar_area(total: &mut ResultadoArea, dia: &ResultadoArea) {
+    total.total += dia.total;
+    total.acertos += dia.acertos;
+    total.erros += dia.erros;
+    total.brancos += dia.brancos;
+    total.tempo_segundos = match (total.tempo_segundos, dia.tempo_segundos) {
+        (Some(a), Some(b)) => Some(a + b),
+        _ => None,
+    };
+}
@@ -0,0 +1,175 @@
+//! Exportação do histórico de simulados em CSV ou JSON para análise em planilhas.
+//! Os nomes de colunas e o formato das datas (RFC 3339 em UTC, ex: `2024-03-10T14:05:00Z`)
+//! fazem parte do contrato com scripts externos: só acrescente colunas no fim.
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FormatoExportacao {
+    Csv,
+    Json,
+}
+
+impl FormatoExportacao {
+    /// Deduz o formato pela extensão do arquivo (`.json`; qualquer outra vira CSV)
+    pub fn pela_extensao(caminho: &Path) -> Self {
+        match caminho.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// Quais simulados exportar; campos vazios não filtram
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct FiltroExportacao {
+    #[serde(default)]
+    pub simulado_ids: Vec<i64>,
+    #[serde(default)]
+    pub vestibular: Option<String>,
+    #[serde(default)]
+    pub prova_id: Option<String>,
+    /// Primeiro dia (UTC) de início considerado, inclusive
+    #[serde(default)]
+    pub desde: Option<NaiveDate>,
+    /// Último dia (UTC) de início considerado, inclusive
+    #[serde(default)]
+    pub ate: Option<NaiveDate>,
+    #[serde(default)]
+    pub incluir_abandonados: bool,
+}
+
+impl FiltroExportacao {
+    pub fn aceita(&self, simulado_id: i64, vestibular: &str, prova_id: &str, iniciado_em: Option<DateTime<Utc>>) -> bool {
+        if !self.simulado_ids.is_empty() && !self.simulado_ids.contains(&simulado_id) {
+            return false;
+        }
+        if self.vestibular.as_deref().is_some_and(|v| v != vestibular) {
+            return false;
+        }
+        if self.prova_id.as_deref().is_some_and(|p| p != prova_id) {
+            return false;
+        }
+        if self.desde.is_none() && self.ate.is_none() {
+            return true;
+        }
+        let Some(dia) = iniciado_em.map(|dt| dt.date_naive()) else {
+            return false;
+        };
+        self.desde.is_none_or(|desde| dia >= desde) && self.ate.is_none_or(|ate| dia <= ate)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AreaExportada {
+    pub area_id: String,
+    pub pontuacao_area: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RespostaExportada {
+    pub questao_id: String,
+    pub numero: u32,
+    pub area_id: String,
+    pub resposta: Option<String>,
+    pub gabarito: String,
+    pub correta: bool,
+}
+
+/// Um simulado do histórico. Sem resultado (em andamento ou abandonado),
+/// `pontuacao`, `areas` e `respostas` ficam vazios.
+#[derive(Serialize, Debug, Clone)]
+pub struct SimuladoExportado {
+    pub simulado_id: i64,
+    pub prova_id: String,
+    pub vestibular: String,
+    pub ano: i32,
+    pub modo: String,
+    pub estado: String,
+    pub iniciado_em: Option<String>,
+    pub finalizado_em: Option<String>,
+    pub pontuacao: Option<f64>,
+    pub areas: Vec<AreaExportada>,
+    pub respostas: Vec<RespostaExportada>,
+}
+
+pub fn formatar_data(data: Option<DateTime<Utc>>) -> Option<String> {
+    data.map(|dt| dt.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+const COLUNAS_CSV: [&str; 16] = [
+    "simulado_id", "prova_id", "vestibular", "ano", "modo", "estado",
+    "iniciado_em", "finalizado_em", "pontuacao",
+    "questao_id", "numero", "area_id", "pontuacao_area", "resposta", "gabarito", "correta",
+];
+
+fn campo_csv(valor: &str) -> String {
+    if valor.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", valor.replace('"', "\"\""))
+    } else {
+        valor.to_string()
+    }
+}
+
+fn numero_csv(valor: f64) -> String {
+    format!("{:.2}", valor)
+}
+
+/// Uma linha por resposta, repetindo os dados do simulado; simulados sem
+/// resultado saem numa única linha com as colunas de questão vazias.
+pub fn gerar_csv(simulados: &[SimuladoExportado]) -> String {
+    let mut csv = COLUNAS_CSV.join(",");
+    csv.push('\n');
+
+    for simulado in simulados {
+        let comuns = [
+            simulado.simulado_id.to_string(),
+            simulado.prova_id.clone(),
+            simulado.vestibular.clone(),
+            simulado.ano.to_string(),
+            simulado.modo.clone(),
+            simulado.estado.clone(),
+            simulado.iniciado_em.clone().unwrap_or_default(),
+            simulado.finalizado_em.clone().unwrap_or_default(),
+            simulado.pontuacao.map(numero_csv).unwrap_or_default(),
+        ];
+
+        let linhas: Vec<[String; 7]> = if simulado.respostas.is_empty() {
+            vec![Default::default()]
+        } else {
+            simulado.respostas.iter()
+                .map(|r| {
+                    let pontuacao_area = simulado.areas.iter()
+                        .find(|a| a.area_id == r.area_id)
+                        .map(|a| numero_csv(a.pontuacao_area))
+                        .unwrap_or_default();
+                    [
+                        r.questao_id.clone(),
+                        r.numero.to_string(),
+                        r.area_id.clone(),
+                        pontuacao_area,
+                        r.resposta.clone().unwrap_or_default(),
+                        r.gabarito.clone(),
+                        r.correta.to_string(),
+                    ]
+                })
+                .collect()
+        };
+
+        for linha in linhas {
+            let campos: Vec<String> = comuns.iter().chain(linha.iter())
+                .map(|c| campo_csv(c))
+                .collect();
+            csv.push_str(&campos.join(","));
+            csv.push('\n');
+        }
+    }
+    csv
+}
+
+pub fn gerar_json(simulados: &[SimuladoExportado]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(simulados)
+}
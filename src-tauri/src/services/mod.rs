@@ -1,2 +1,11 @@
 pub mod prova_service;
-pub mod simulado_service;
\ No newline at end of file
+pub mod simulado_service;
+pub mod edicao_service;
+pub mod boletim;
+pub mod exportacao;
+pub mod caderno_service;
+pub mod revisao_service;
+pub mod sisu_service;
+pub mod backup_service;
+
+pub mod perfil_service;
\ No newline at end of file
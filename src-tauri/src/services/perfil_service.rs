@@ -0,0 +1,103 @@
+use crate::domain::perfil::{self, Perfil};
+use crate::persistence::perfil_repository::PerfilRepository;
+use crate::services::simulado_service::SimuladoService;
+use anyhow::{Result, anyhow};
+use std::sync::Arc;
+
+pub struct PerfilService {
+    repo: PerfilRepository,
+    simulados: Arc<SimuladoService>,
+}
+
+impl PerfilService {
+    pub fn new(repo: PerfilRepository, simulados: Arc<SimuladoService>) -> Self {
+        Self { repo, simulados }
+    }
+
+    pub fn listar(&self) -> Result<Vec<Perfil>> {
+        Ok(self.repo.listar()?)
+    }
+
+    pub fn ativo(&self) -> Result<Perfil> {
+        let id = self.repo.ativo()?;
+        self.buscar(id)
+    }
+
+    /// Cria o perfil sem trocar para ele
+    pub fn criar(&self, nome: &str, pin: Option<&str>) -> Result<Perfil> {
+        let nome = perfil::validar_nome(nome).map_err(|e| anyhow!(e))?;
+        let pin_hash = match pin {
+            Some(pin) => {
+                perfil::validar_pin(pin).map_err(|e| anyhow!(e))?;
+                Some(perfil::gerar_hash_pin(pin).map_err(|e| anyhow!(e))?)
+            }
+            None => None,
+        };
+        
+        let id = self.repo.criar(&nome, pin_hash.as_deref())?;
+        println!("👤 Perfil {} criado: {}", id, nome);
+        self.buscar(id)
+    }
+
+    pub fn renomear(&self, id: i64, nome: &str, pin: Option<&str>) -> Result<Perfil> {
+        self.autorizar(id, pin)?;
+        let nome = perfil::validar_nome(nome).map_err(|e| anyhow!(e))?;
+        self.repo.renomear(id, &nome)?;
+        println!("✏️ Perfil {} renomeado para {}", id, nome);
+        self.buscar(id)
+    }
+
+    /// Troca (`novo_pin` com valor) ou remove (`None`) o PIN; pede o atual, se houver
+    pub fn definir_pin(&self, id: i64, pin_atual: Option<&str>, novo_pin: Option<&str>) -> Result<Perfil> {
+        self.autorizar(id, pin_atual)?;
+        let pin_hash = match novo_pin {
+            Some(pin) => {
+                perfil::validar_pin(pin).map_err(|e| anyhow!(e))?;
+                Some(perfil::gerar_hash_pin(pin).map_err(|e| anyhow!(e))?)
+            }
+            None => None,
+        };
+        self.repo.definir_pin(id, pin_hash.as_deref())?;
+        println!("🔑 PIN do perfil {} {}", id, if novo_pin.is_some() { "definido" } else { "removido" });
+        self.buscar(id)
+    }
+
+    /// Entra no perfil. Os simulados em andamento são pausados antes, para que o
+    /// cronômetro de um aluno não corra enquanto outro usa o app.
+    pub fn trocar(&self, id: i64, pin: Option<&str>) -> Result<Perfil> {
+        let perfil = self.autorizar(id, pin)?;
+        self.simulados.pausar_simulados_ativos()?;
+        self.repo.ativar(id)?;
+        println!("🔄 Perfil ativo: {} ({})", perfil.nome, id);
+        Ok(perfil)
+    }
+
+    /// Apaga o perfil e todo o histórico dele. O perfil ativo não pode ser
+    /// excluído (troque antes), o que também garante que sempre reste um.
+    pub fn excluir(&self, id: i64, pin: Option<&str>) -> Result<()> {
+        self.autorizar(id, pin)?;
+        if self.repo.ativo()? == id {
+            return Err(anyhow!("Troque para outro perfil antes de excluir este"));
+        }
+        self.repo.excluir(id)?;
+        println!("🗑️ Perfil {} excluído com todo o histórico", id);
+        Ok(())
+    }
+
+    fn buscar(&self, id: i64) -> Result<Perfil> {
+        self.repo.buscar_por_id(id)?
+            .ok_or_else(|| anyhow!("Perfil {} não encontrado", id))
+    }
+
+    /// Confere o PIN quando o perfil tem um
+    fn autorizar(&self, id: i64, pin: Option<&str>) -> Result<Perfil> {
+        let perfil = self.buscar(id)?;
+        if let Some(pin_hash) = self.repo.pin_hash(id)? {
+            let pin = pin.ok_or_else(|| anyhow!("Informe o PIN do perfil {}", perfil.nome))?;
+            if !perfil::conferir_pin(&pin_hash, pin) {
+                return Err(anyhow!("PIN incorreto"));
+            }
+        }
+        Ok(perfil)
+    }
+}
@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use crate::domain::prova::{Prova, ProvaError};
+use crate::domain::regras::RegrasPontuacao;
+use crate::domain::estatisticas::EstatisticasProva;
 
 pub struct ProvaService {
     provas_dir: PathBuf,
@@ -88,6 +90,47 @@ pub fn carregar(&self, prova_id: &str) -> Result<Prova, ProvaServiceError> {
     Ok(prova)
 }
 
+    /// Regras de pontuação da prova: as do próprio `prova.json` ou, na falta delas,
+    /// as de `provas/{vestibular}/regras.json`.
+    pub fn carregar_regras(&self, prova_id: &str, prova: &Prova) -> Result<Option<RegrasPontuacao>, ProvaServiceError> {
+        if let Some(regras) = &prova.regras {
+            regras.validar().map_err(|motivo| ProvaServiceError::RegrasInvalidas(
+                self.provas_dir.join(prova_id).join("prova.json"), motivo,
+            ))?;
+            return Ok(Some(regras.clone()));
+        }
+
+        let Some(vestibular) = prova_id.split('/').next() else {
+            return Ok(None);
+        };
+        let regras_path = self.provas_dir.join(vestibular).join("regras.json");
+        if !regras_path.exists() {
+            return Ok(None);
+        }
+
+        let conteudo = fs::read_to_string(&regras_path)
+            .map_err(|e| ProvaServiceError::LeituraFalhou(regras_path.clone(), e))?;
+        let regras: RegrasPontuacao = serde_json::from_str(&conteudo)
+            .map_err(|e| ProvaServiceError::ParseJson(regras_path.clone(), e))?;
+        regras.validar()
+            .map_err(|motivo| ProvaServiceError::RegrasInvalidas(regras_path, motivo))?;
+        Ok(Some(regras))
+    }
+
+    /// Estatísticas oficiais da prova, de `estatisticas.json` ao lado do `prova.json`
+    pub fn carregar_estatisticas(&self, prova_id: &str) -> Result<Option<EstatisticasProva>, ProvaServiceError> {
+        let estatisticas_path = self.provas_dir.join(prova_id).join("estatisticas.json");
+        if !estatisticas_path.exists() {
+            return Ok(None);
+        }
+
+        let conteudo = fs::read_to_string(&estatisticas_path)
+            .map_err(|e| ProvaServiceError::LeituraFalhou(estatisticas_path.clone(), e))?;
+        let estatisticas = serde_json::from_str(&conteudo)
+            .map_err(|e| ProvaServiceError::ParseJson(estatisticas_path, e))?;
+        Ok(Some(estatisticas))
+    }
+
     pub fn questao_existe(&self, prova_id: &str, questao_id: &str) -> Result<bool, String> {
         match self.carregar(prova_id) {
             Ok(prova) => {
@@ -113,4 +156,6 @@ pub enum ProvaServiceError {
     ParseJson(PathBuf, #[source] serde_json::Error),
     #[error("Falha na validação da prova: {0}")]
     Validacao(#[from] ProvaError),
+    #[error("Regras de pontuação inválidas em {0}: {1}")]
+    RegrasInvalidas(PathBuf, String),
 }
\ No newline at end of file
@@ -0,0 +1,114 @@
+use crate::domain::repeticao::CartaoRevisao;
+use crate::domain::questao::Questao;
+use crate::persistence::revisao_repository::RevisaoRepository;
+use crate::services::prova_service::ProvaService;
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDate};
+use std::path::PathBuf;
+
+/// Questão da revisão do dia
+#[derive(serde::Serialize)]
+pub struct QuestaoRevisao {
+    pub cartao: CartaoRevisao,
+    pub questao: Questao,
+}
+
+#[derive(serde::Serialize)]
+pub struct CorrecaoRevisao {
+    pub cartao_id: i64,
+    pub acertou: bool,
+    pub gabarito: String,
+    pub explicacao: Option<String>,
+    /// Nota SM-2 efetivamente aplicada (0 a 5)
+    pub nota: u8,
+    pub intervalo_dias: u32,
+    pub proxima_revisao: NaiveDate,
+}
+
+pub struct RevisaoService {
+    repo: RevisaoRepository,
+    provas_dir: PathBuf,
+}
+
+/// Dia de revisão no fuso do aluno
+fn hoje() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+impl RevisaoService {
+    pub fn new(repo: RevisaoRepository, provas_dir: PathBuf) -> Self {
+        Self { repo, provas_dir }
+    }
+
+    fn carregar_questao(&self, prova_id: &str, questao_id: &str) -> Result<Option<Questao>> {
+        let prova = ProvaService::new(self.provas_dir.clone())
+            .carregar(prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar prova {}: {}", prova_id, e))?;
+        Ok(prova.questoes.into_iter().find(|q| q.id == questao_id))
+    }
+
+    /// Coloca questões da biblioteca na revisão, pendentes a partir de hoje.
+    /// Sem `questao_ids`, entra a prova inteira. Retorna quantas eram novas.
+    pub fn adicionar_questoes(&self, prova_id: &str, questao_ids: Option<Vec<String>>) -> Result<usize> {
+        let prova = ProvaService::new(self.provas_dir.clone())
+            .carregar(prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar prova {}: {}", prova_id, e))?;
+        
+        let mut novas = 0;
+        for questao in &prova.questoes {
+            if questao_ids.as_ref().is_some_and(|ids| !ids.contains(&questao.id)) {
+                continue;
+            }
+            if self.repo.adicionar(prova_id, &questao.id, &questao.area_id, hoje())? {
+                novas += 1;
+            }
+        }
+        
+        println!("🔁 {} questões de {} adicionadas à revisão", novas, prova_id);
+        Ok(novas)
+    }
+
+    /// Questões com revisão marcada para hoje ou atrasadas
+    pub fn revisao_do_dia(&self, limite: Option<usize>) -> Result<Vec<QuestaoRevisao>> {
+        let mut revisao = Vec::new();
+        for cartao in self.repo.listar_pendentes(hoje())? {
+            if limite.is_some_and(|limite| revisao.len() >= limite) {
+                break;
+            }
+            match self.carregar_questao(&cartao.prova_id, &cartao.questao_id) {
+                Ok(Some(questao)) => revisao.push(QuestaoRevisao { cartao, questao }),
+                Ok(None) => println!("⚠️ Questão {} não existe mais na prova {}", cartao.questao_id, cartao.prova_id),
+                Err(e) => println!("⚠️ {}", e),
+            }
+        }
+        Ok(revisao)
+    }
+
+    /// Corrige a resposta e reagenda o cartão. A `nota` (0 a 5) é a autoavaliação do
+    /// aluno: num acerto vale entre 3 e 5 (padrão 4), num erro no máximo 2 (padrão 1).
+    pub fn responder_revisao(&self, cartao_id: i64, alternativa: String, nota: Option<u8>) -> Result<CorrecaoRevisao> {
+        let mut cartao = self.repo.buscar_por_id(cartao_id)?
+            .ok_or_else(|| anyhow!("Cartão de revisão {} não encontrado", cartao_id))?;
+        let questao = self.carregar_questao(&cartao.prova_id, &cartao.questao_id)?
+            .ok_or_else(|| anyhow!("Questão {} não existe mais na prova {}", cartao.questao_id, cartao.prova_id))?;
+        
+        let acertou = alternativa == questao.resposta_correta;
+        let nota = if acertou {
+            nota.unwrap_or(4).clamp(3, 5)
+        } else {
+            nota.unwrap_or(1).min(2)
+        };
+        cartao.revisar(nota, hoje());
+        self.repo.salvar(&cartao)?;
+        
+        Ok(CorrecaoRevisao {
+            cartao_id,
+            acertou,
+            gabarito: questao.resposta_correta,
+            explicacao: questao.explicacao,
+            nota,
+            intervalo_dias: cartao.intervalo_dias,
+            proxima_revisao: cartao.proxima_revisao,
+        })
+    }
+}
@@ -1,48 +1,102 @@
 use crate::domain::{estado::EstadoSimulado, simulado::Simulado};
-use crate::domain::estado::EstadoSimuladoCompleto; 
-use crate::persistence::repository::SimuladoRepository;
+use crate::domain::estado::{EstadoSimuladoCompleto, ModoSimulado, ModoTempo}; 
+use crate::domain::questao::ParametrosTri;
+use crate::domain::tri;
+use crate::domain::regras::ItemPontuado;
+use crate::domain::resultado::{percentual, DetalheQuestao, EstatisticaQuestao, ResultadoArea, ResultadoSimulado};
+use crate::domain::comparacao::{self, ComparacaoTentativas, Tentativa};
+use crate::domain::estatisticas::{self, Comparativo, PercentilArea};
+use crate::persistence::repository::{SimuladoRepository, DIAS_LIXEIRA_MAXIMO};
 use crate::state::transitions;
+use crate::state::relogio::RelogioSessao;
 use crate::services::prova_service::ProvaService; 
+use crate::services::boletim::{self, DadosBoletim, FormatoBoletim};
+use crate::services::exportacao::{self, AreaExportada, FiltroExportacao, FormatoExportacao, RespostaExportada, SimuladoExportado};
 use anyhow::{Result, anyhow};
-use chrono::Utc;
-use std::path::PathBuf;
+use chrono::{Duration, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::env;
 
 #[derive(serde::Serialize)]
 pub struct SimuladoResumo {
     pub id: i64,
+    pub prova_id: String,
     pub vestibular: String,
     pub ano: i32,
     pub iniciado_em: Option<String>,
     pub estado: String,
+    pub modo: ModoSimulado,
+    /// Motivo quando o estado gravado não pôde ser lido (`estado` = "Corrompido")
+    pub erro: Option<String>,
+    /// Edição e dia a que o simulado pertence, para o histórico mostrar os dias juntos
+    pub edicao_id: Option<i64>,
+    pub dia: Option<u8>,
 }
 
+/// Simulado na lixeira, com a data em que será apagado de vez
 #[derive(serde::Serialize)]
-pub struct DetalheQuestao {
+pub struct ItemLixeira {
+    pub id: i64,
+    pub prova_id: String,
+    pub vestibular: String,
+    pub ano: i32,
+    pub iniciado_em: Option<String>,
+    pub excluido_em: Option<String>,
+    pub expira_em: Option<String>,
+}
+
+/// Correção devolvida a cada resposta no modo estudo
+#[derive(serde::Serialize)]
+pub struct CorrecaoQuestao {
     pub questao_id: String,
-    pub numero: u32,
-    pub resposta_usuario: Option<String>,
-    pub gabarito: String,
+    pub alternativa_marcada: String,
     pub acertou: bool,
+    pub gabarito: String,
+    pub explicacao: Option<String>,
 }
 
-#[derive(serde::Serialize)]
-pub struct ResultadoSimulado {
-    pub total_questoes: usize,
-    pub acertos: usize,
-    pub erros: usize,
-    pub pontuacao: f64,
-    pub detalhes: Vec<DetalheQuestao>, 
+/// Aviso de tempo restante emitido como evento Tauri (`alerta-tempo`)
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct AlertaTempo {
+    pub simulado_id: i64,
+    pub limiar_minutos: u32,
+    pub restante_segundos: u32,
+}
+
+/// O que fazer, na inicialização, com simulados que ficaram em andamento
+/// porque o app foi fechado à força ou travou. Escolhida pelo aluno e
+/// guardada em `configuracao`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PoliticaRecuperacao {
+    /// Pausa no último heartbeat recebido; o tempo offline não é cobrado
+    #[default]
+    PausarNoUltimoHeartbeat,
+    /// Cobra todo o tempo real até agora (pode finalizar por tempo) e pausa
+    CobrarTempoReal,
 }
 
+/// Maior duração aceita para uma prova feita em papel
+const DURACAO_MAXIMA_FOLHA_MINUTOS: u32 = 24 * 60;
+
 pub struct SimuladoService {
     repo: SimuladoRepository,
     provas_dir: PathBuf, 
+    relogio: RelogioSessao,
 }
 
 impl SimuladoService {
     pub fn new(repo: SimuladoRepository, provas_dir: PathBuf) -> Self {
-        Self { repo, provas_dir }
+        Self { repo, provas_dir, relogio: RelogioSessao::new() }
+    }
+
+    /// Duração do intervalo ativo em curso, medida pelo relógio monotônico da sessão
+    fn intervalo_atual(&self, simulado_id: i64, estado: &EstadoSimuladoCompleto) -> u32 {
+        match (estado.estado == EstadoSimulado::EmAndamento, estado.tempo.inicio_intervalo) {
+            (true, Some(inicio_intervalo)) => self.relogio.segundos_intervalo(simulado_id, inicio_intervalo),
+            _ => 0,
+        }
     }
     
     fn prova_existe(&self, prova_id: &str) -> Result<bool> {
@@ -59,16 +113,28 @@ impl SimuladoService {
         vestibular: String,
         ano: i32,
         duracao_minutos: i32,
+        modo: ModoSimulado,
     ) -> Result<i64> {
 
           if !self.prova_existe(&prova_id)? {
         return Err(anyhow!("Prova '{}' não encontrada", prova_id));
     }
     
+        // No modo estudo não há pressão de tempo: sem limite e sem alertas
+        let duracao_minutos = match modo {
+            ModoSimulado::Prova => duracao_minutos,
+            ModoSimulado::Estudo => 0,
+        };
+    
         let mut simulado = Simulado::novo(prova_id, vestibular, ano, duracao_minutos)?;
         let mut estado = simulado.estado()?;
         
         estado.tempo.limite_minutos = duracao_minutos as u16;
+        estado.modo = modo;
+        if modo == ModoSimulado::Estudo {
+            estado.modo_tempo = ModoTempo::Livre;
+            estado.configuracoes.alertas_minutos.clear();
+        }
         
         transitions::iniciar(&mut estado)?;
         
@@ -81,34 +147,61 @@ impl SimuladoService {
         
         let id = self.repo.salvar(&simulado)?;
         
-        println!(" Simulado iniciado com ID: {}, tempo.inicio: {:?}", id, estado.tempo.inicio);
+        println!(" Simulado iniciado com ID: {} ({:?}), tempo.inicio: {:?}", id, modo, estado.tempo.inicio);
         Ok(id)
     }
 
-    pub fn atualizar_tempo(&self, simulado_id: i64) -> Result<()> {
+    /// Atualiza o tempo decorrido (heartbeat do frontend) e retorna um alerta
+    /// para cada limiar de tempo restante cruzado desde o último heartbeat.
+    /// Não há timer no backend: os alertas só saem quando o frontend chama
+    /// `atualizar_tempo_simulado`, então chegam com o atraso do seu intervalo.
+    pub fn atualizar_tempo(&self, simulado_id: i64) -> Result<Vec<AlertaTempo>> {
         let mut simulado = self.repo.buscar_por_id(simulado_id)?
             .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
         
         let mut estado = simulado.estado()?;
+        let mut alertas = Vec::new();
         
         if estado.estado == EstadoSimulado::EmAndamento {
-            if let Some(inicio) = estado.tempo.inicio {
-                let agora = Utc::now();
-                let decorrido_total = agora.signed_duration_since(inicio).num_seconds();
-                
-               // println!(" Atualizando tempo: ID={}, inicio={:?}, agora={:?}, decorrido={}", 
-                 //   simulado_id, inicio, agora, decorrido_total);
-                    
-                estado.tempo.decorrido_segundos = decorrido_total.max(0) as u32;
-                
-                simulado.set_estado(&estado)?;
-                self.repo.salvar(&simulado)?;
-            } else {
-                println!("❌ ERRO: tempo.inicio é None para simulado {}", simulado_id);
-                return Err(anyhow!("Tempo não foi iniciado para simulado {}", simulado_id));
+            let intervalo = self.intervalo_atual(simulado_id, &estado);
+            transitions::atualizar_decorrido(&mut estado, intervalo)
+                .map_err(|e| anyhow!("Simulado {}: {}", simulado_id, e))?;
+            estado.tempo.ultimo_heartbeat = Some(Utc::now());
+            
+            let limite_segundos = estado.tempo.limite_minutos as u32 * 60;
+            for limiar_minutos in transitions::verificar_alertas(&mut estado) {
+                println!("🔔 Simulado {}: alerta de {} minutos restantes", simulado_id, limiar_minutos);
+                alertas.push(AlertaTempo {
+                    simulado_id,
+                    limiar_minutos,
+                    restante_segundos: limite_segundos.saturating_sub(estado.tempo.decorrido_segundos),
+                });
             }
+            
+            simulado.set_estado(&estado)?;
+            self.repo.salvar(&simulado)?;
         }
         
+        Ok(alertas)
+    }
+
+    /// Define os limiares de alerta (minutos restantes) do simulado.
+    /// Limiares que já dispararam e continuam configurados não disparam de novo.
+    pub fn configurar_alertas_tempo(&self, simulado_id: i64, limiares_minutos: Vec<u32>) -> Result<()> {
+        let mut simulado = self.repo.buscar_por_id(simulado_id)?
+            .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
+        
+        let mut estado = simulado.estado()?;
+        
+        let mut limiares: Vec<u32> = limiares_minutos.into_iter().filter(|m| *m > 0).collect();
+        limiares.sort_unstable_by(|a, b| b.cmp(a));
+        limiares.dedup();
+        
+        estado.tempo.alertas_disparados.retain(|m| limiares.contains(m));
+        estado.configuracoes.alertas_minutos = limiares;
+        
+        simulado.set_estado(&estado)?;
+        self.repo.salvar(&simulado)?;
         Ok(())
     }
 
@@ -118,7 +211,9 @@ impl SimuladoService {
         
         let mut estado = simulado.estado()?;
         
-        transitions::pausar(&mut estado)?;
+        let intervalo = self.intervalo_atual(simulado_id, &estado);
+        transitions::pausar(&mut estado, intervalo)?;
+        self.relogio.encerrar(simulado_id);
         
         simulado.set_estado(&estado)?;
         self.repo.salvar(&simulado)?;
@@ -127,6 +222,83 @@ impl SimuladoService {
         Ok(())
     }
 
+    /// Pausa todos os simulados em andamento, de qualquer perfil. Chamado quando
+    /// a janela é fechada e na troca de perfil.
+    pub fn pausar_simulados_ativos(&self) -> Result<usize> {
+        let mut pausados = 0;
+        for mut simulado in self.repo.listar_de_todos_os_perfis()? {
+            let Ok(mut estado) = simulado.estado() else {
+                continue;
+            };
+            if estado.estado != EstadoSimulado::EmAndamento {
+                continue;
+            }
+            
+            let intervalo = self.intervalo_atual(simulado.id, &estado);
+            transitions::pausar(&mut estado, intervalo)?;
+            self.relogio.encerrar(simulado.id);
+            simulado.set_estado(&estado)?;
+            self.repo.salvar(&simulado)?;
+            pausados += 1;
+        }
+        
+        if pausados > 0 {
+            println!("⏸️ {} simulado(s) pausado(s) no fechamento", pausados);
+        }
+        Ok(pausados)
+    }
+
+    /// Aplica a política de recuperação aos simulados que ficaram `EmAndamento`
+    /// depois de um fechamento inesperado. Retorna os IDs recuperados.
+    pub fn recuperar_simulados_interrompidos(&self, politica: PoliticaRecuperacao) -> Result<Vec<i64>> {
+        let mut recuperados = Vec::new();
+        
+        for mut simulado in self.repo.listar_de_todos_os_perfis()? {
+            let mut estado = match simulado.estado() {
+                Ok(estado) => estado,
+                Err(e) => {
+                    println!("⚠️ Estado ilegível no simulado {}: {}", simulado.id, e);
+                    continue;
+                }
+            };
+            if estado.estado != EstadoSimulado::EmAndamento {
+                continue;
+            }
+            
+            match politica {
+                PoliticaRecuperacao::PausarNoUltimoHeartbeat => {
+                    // O decorrido gravado no último heartbeat é a melhor medida do tempo ativo
+                    let intervalo = estado.tempo.decorrido_segundos
+                        .saturating_sub(estado.tempo.acumulado_segundos);
+                    transitions::pausar(&mut estado, intervalo)?;
+                }
+                PoliticaRecuperacao::CobrarTempoReal => {
+                    // Sem âncora monotônica nesta execução: o relógio de parede é a única referência
+                    let intervalo = self.intervalo_atual(simulado.id, &estado);
+                    transitions::verificar_expiracao_tempo(&mut estado, intervalo)?;
+                    if estado.estado == EstadoSimulado::EmAndamento {
+                        transitions::pausar(&mut estado, intervalo)?;
+                    } else if estado.tempo.finalizado_em.is_some() {
+                        simulado.finalizado_em = estado.tempo.finalizado_em;
+                    }
+                    self.relogio.encerrar(simulado.id);
+                }
+            }
+            
+            simulado.set_estado(&estado)?;
+            if estado.estado.esta_finalizado() {
+                self.persistir_finalizacao(&simulado, &estado)?;
+            } else {
+                self.repo.salvar(&simulado)?;
+            }
+            println!("🩹 Simulado {} recuperado ({:?}): decorrido={}s, estado={:?}",
+                simulado.id, politica, estado.tempo.decorrido_segundos, estado.estado);
+            recuperados.push(simulado.id);
+        }
+        
+        Ok(recuperados)
+    }
+
     pub fn retomar_simulado(&self, simulado_id: i64) -> Result<()> {
         let mut simulado = self.repo.buscar_por_id(simulado_id)?
             .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
@@ -141,10 +313,43 @@ impl SimuladoService {
         simulado.set_estado(&estado)?;
         self.repo.salvar(&simulado)?;
         
-        println!("▶️ Simulado {} retomado. Novo tempo.inicio: {:?}", simulado_id, estado.tempo.inicio);
+        println!("▶️ Simulado {} retomado. Novo intervalo em: {:?}", simulado_id, estado.tempo.inicio_intervalo);
         Ok(())
     }
 
+    /// Desiste de um simulado em andamento ou pausado
+    pub fn abandonar_simulado(&self, simulado_id: i64) -> Result<()> {
+        let mut simulado = self.repo.buscar_por_id(simulado_id)?
+            .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
+        
+        let mut estado = simulado.estado()?;
+        let intervalo = self.intervalo_atual(simulado_id, &estado);
+        transitions::abandonar(&mut estado, intervalo)?;
+        self.relogio.encerrar(simulado_id);
+        
+        simulado.set_estado(&estado)?;
+        self.repo.salvar(&simulado)?;
+        
+        println!("🏳️ Simulado {} abandonado após {}s", simulado_id, estado.tempo.decorrido_segundos);
+        Ok(())
+    }
+
+    /// Abre um simulado finalizado para revisão. Nada é gravado: a navegação
+    /// funciona como sempre e `registrar_resposta` já recusa simulados finalizados.
+    pub fn revisar_simulado(&self, simulado_id: i64) -> Result<EstadoSimuladoCompleto> {
+        let simulado = self.buscar_simulado(simulado_id)?;
+        let estado = simulado.estado()?;
+        if !estado.estado.pode_revisar() {
+            return Err(anyhow!("Só simulados finalizados podem ser revisados (estado atual: {:?})", estado.estado));
+        }
+        Ok(estado)
+    }
+
+    pub fn buscar_simulado(&self, simulado_id: i64) -> Result<Simulado> {
+        self.repo.buscar_por_id(simulado_id)?
+            .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))
+    }
+
     pub fn obter_estado(&self, simulado_id: i64) -> Result<EstadoSimuladoCompleto> {
         let simulado = self.repo.buscar_por_id(simulado_id)?
             .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
@@ -183,6 +388,17 @@ impl SimuladoService {
         Ok(existe)
     }
 
+    /// Atribui à questão atual o tempo ativo gasto nela antes de navegar
+    fn creditar_tempo_questao(&self, simulado_id: i64, estado: &mut EstadoSimuladoCompleto) -> Result<()> {
+        if estado.estado != EstadoSimulado::EmAndamento {
+            return Ok(());
+        }
+        let intervalo = self.intervalo_atual(simulado_id, estado);
+        transitions::atualizar_decorrido(estado, intervalo)?;
+        transitions::registrar_tempo_questao(estado);
+        Ok(())
+    }
+
     pub fn voltar_questao(&self, simulado_id: i64) -> Result<()> {
         let mut simulado = self.repo.buscar_por_id(simulado_id)?
             .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
@@ -204,6 +420,7 @@ impl SimuladoService {
                 questao_anterior, simulado.prova_id));
         }
         
+        self.creditar_tempo_questao(simulado_id, &mut estado)?;
         estado.progresso.questao_atual = questao_anterior;
         
         simulado.set_estado(&estado)?;
@@ -226,6 +443,7 @@ impl SimuladoService {
                 simulado.prova_id));
         }
         
+        self.creditar_tempo_questao(simulado_id, &mut estado)?;
         estado.progresso.questao_atual = proxima_questao;
         
         simulado.set_estado(&estado)?;
@@ -233,16 +451,22 @@ impl SimuladoService {
         Ok(())
     }
 
+    /// Registra a resposta. No modo estudo a questão é corrigida na hora,
+    /// a correção é devolvida e a resposta fica bloqueada.
     pub fn registrar_resposta(
         &self,
         simulado_id: i64,
         questao_id: String,
         alternativa: Option<String>,
-    ) -> Result<()> {
+    ) -> Result<Option<CorrecaoQuestao>> {
         let mut simulado = self.repo.buscar_por_id(simulado_id)?
             .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
         
         let mut estado = simulado.estado()?;
+        
+        if !estado.estado.pode_responder() {
+            return Err(anyhow!("Respostas bloqueadas: simulado {} está {:?}", simulado_id, estado.estado));
+        }
 
         println!("📝 Questão {} respondida: {:?}, respondidas: {}", 
         questao_id, alternativa, estado.progresso.respondidas);
@@ -250,6 +474,21 @@ impl SimuladoService {
         let era_respondida = estado.respostas.contains_key(&questao_id) 
             && estado.respostas[&questao_id].is_some();
         
+        let correcao = if estado.modo == ModoSimulado::Estudo {
+            if era_respondida {
+                return Err(anyhow!("Questão {} já foi corrigida e não pode ser alterada", questao_id));
+            }
+            let marcada = alternativa.clone()
+                .ok_or_else(|| anyhow!("No modo estudo é preciso marcar uma alternativa"))?;
+            Some(self.corrigir_questao(&simulado.prova_id, &questao_id, marcada)?)
+        } else {
+            None
+        };
+        
+        // No modo prova a linha fica sem correção até a finalização, que preenche
+        // `correta` em `gravar_resultado`; assim marcar não precisa ler a prova
+        let correta = correcao.as_ref().map(|c| c.acertou);
+        
         let agora_respondida = alternativa.is_some();
         
         if !era_respondida && agora_respondida {
@@ -258,11 +497,72 @@ impl SimuladoService {
             estado.progresso.respondidas = estado.progresso.respondidas.saturating_sub(1);
         }
         
-        estado.respostas.insert(questao_id, alternativa);
+        estado.respostas.insert(questao_id.clone(), alternativa.clone());
+        
+        simulado.set_estado(&estado)?;
+        self.repo.salvar_com_resposta(&simulado, &questao_id, alternativa.as_deref(), correta)?;
+        Ok(correcao)
+    }
+
+    /// Risca ou desrisca uma alternativa da questão. Retorna as alternativas riscadas.
+    pub fn alternar_alternativa_eliminada(
+        &self,
+        simulado_id: i64,
+        questao_id: String,
+        alternativa: String,
+    ) -> Result<Vec<String>> {
+        let mut simulado = self.repo.buscar_por_id(simulado_id)?
+            .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
+        
+        let mut estado = simulado.estado()?;
+        
+        if !estado.estado.pode_responder() {
+            return Err(anyhow!("Alternativas bloqueadas: simulado {} está {:?}", simulado_id, estado.estado));
+        }
+        if estado.modo == ModoSimulado::Estudo && estado.respostas.get(&questao_id).is_some_and(|r| r.is_some()) {
+            return Err(anyhow!("Questão {} já foi corrigida e não pode ser alterada", questao_id));
+        }
+        
+        let prova = ProvaService::new(self.provas_dir.clone())
+            .carregar(&simulado.prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar prova {}: {}", simulado.prova_id, e))?;
+        let questao = prova.questoes.iter()
+            .find(|q| q.id == questao_id)
+            .ok_or_else(|| anyhow!("Questão {} não encontrada na prova {}", questao_id, simulado.prova_id))?;
+        if !questao.alternativas.iter().any(|a| a.id == alternativa) {
+            return Err(anyhow!("Alternativa {} não existe na questão {}", alternativa, questao_id));
+        }
+        
+        let riscadas = estado.eliminadas.entry(questao_id.clone()).or_default();
+        if !riscadas.remove(&alternativa) {
+            riscadas.insert(alternativa);
+        }
+        let atuais: Vec<String> = riscadas.iter().cloned().collect();
+        if atuais.is_empty() {
+            estado.eliminadas.remove(&questao_id);
+        }
         
         simulado.set_estado(&estado)?;
         self.repo.salvar(&simulado)?;
-        Ok(())
+        Ok(atuais)
+    }
+
+    fn corrigir_questao(&self, prova_id: &str, questao_id: &str, marcada: String) -> Result<CorrecaoQuestao> {
+        let prova = ProvaService::new(self.provas_dir.clone())
+            .carregar(prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar prova {}: {}", prova_id, e))?;
+        
+        let questao = prova.questoes.iter()
+            .find(|q| q.id == questao_id)
+            .ok_or_else(|| anyhow!("Questão {} não encontrada na prova {}", questao_id, prova_id))?;
+        
+        Ok(CorrecaoQuestao {
+            questao_id: questao.id.clone(),
+            acertou: marcada == questao.resposta_correta,
+            alternativa_marcada: marcada,
+            gabarito: questao.resposta_correta.clone(),
+            explicacao: questao.explicacao.clone(),
+        })
     }
 
     pub fn finalizar_simulado(&self, simulado_id: i64) -> Result<()> {
@@ -270,74 +570,581 @@ impl SimuladoService {
             .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
         
         let mut estado = simulado.estado()?;
-        transitions::finalizar(&mut estado)?;
+        let intervalo = self.intervalo_atual(simulado_id, &estado);
+        transitions::finalizar(&mut estado, intervalo)?;
+        self.relogio.encerrar(simulado_id);
         simulado.set_estado(&estado)?;
         simulado.finalizado_em = Some(Utc::now());
-        self.repo.salvar(&simulado)?;
+        self.persistir_finalizacao(&simulado, &estado)?;
         Ok(())
     }
 
-    pub fn calcular_resultado(&self, simulado_id: i64) -> Result<ResultadoSimulado> {
-        let simulado = self.repo.buscar_por_id(simulado_id)?
-            .ok_or_else(|| anyhow!("Simulado {} não encontrado", simulado_id))?;
+    /// Grava o simulado finalizado e o snapshot do resultado na mesma transação,
+    /// para que editar a prova depois não reescreva notas passadas.
+    fn persistir_finalizacao(&self, simulado: &Simulado, estado: &EstadoSimuladoCompleto) -> Result<ResultadoSimulado> {
+        let mut resultado = self.montar_resultado(simulado, estado)?;
+        self.repo.salvar_finalizado(simulado, &mut resultado)?;
+        Ok(resultado)
+    }
+
+    /// Corrige uma prova feita em papel a partir da folha de respostas digitada
+    /// (ex: "DCBAE-C..."), gravando-a como um simulado finalizado no histórico.
+    /// Cada caractere é uma questão; `-`, `_`, `.`, `*` ou espaço marcam questão em branco.
+    pub fn corrigir_folha_respostas(
+        &self,
+        prova_id: String,
+        folha: &str,
+        duracao_minutos: Option<u32>,
+    ) -> Result<ResultadoSimulado> {
+        let duracao_minutos = duracao_minutos.unwrap_or(0);
+        if duracao_minutos > DURACAO_MAXIMA_FOLHA_MINUTOS {
+            return Err(anyhow!("Duração de {} minutos inválida: o máximo é {} (24 horas)",
+                duracao_minutos, DURACAO_MAXIMA_FOLHA_MINUTOS));
+        }
         
+        let prova = ProvaService::new(self.provas_dir.clone())
+            .carregar(&prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar prova {}: {}", prova_id, e))?;
+        
+        let marcacoes: Vec<char> = folha.trim_end_matches(['\n', '\r']).chars().collect();
+        if marcacoes.len() > prova.questoes.len() {
+            return Err(anyhow!("A folha tem {} marcações, mas a prova tem {} questões",
+                marcacoes.len(), prova.questoes.len()));
+        }
+        
+        let mut estado = EstadoSimuladoCompleto::default();
+        for (i, questao) in prova.questoes.iter().enumerate() {
+            let resposta = match marcacoes.get(i) {
+                None | Some(' ' | '-' | '_' | '.' | '*') => None,
+                Some(c) => {
+                    let letra = c.to_uppercase().to_string();
+                    if !questao.alternativas.iter().any(|a| a.id == letra) {
+                        return Err(anyhow!("Marcação '{}' inválida para a questão {}", c, questao.id));
+                    }
+                    Some(letra)
+                }
+            };
+            if resposta.is_some() {
+                estado.progresso.respondidas += 1;
+            }
+            estado.respostas.insert(questao.id.clone(), resposta);
+        }
+        estado.progresso.total = prova.questoes.len();
+        
+        let mut simulado = Simulado::novo(prova_id, prova.vestibular.clone(), prova.ano as i32, prova.duracao_minutos as i32)?;
+        estado.tempo.limite_minutos = prova.duracao_minutos;
+        
+        transitions::iniciar(&mut estado)?;
+        transitions::finalizar(&mut estado, duracao_minutos * 60)?;
+        estado.tempo_questoes.clear(); // no papel o tempo por questão não é conhecido
+        
+        // A prova foi feita antes de ser digitada: o início é recuado pela duração informada
+        let finalizado_em = estado.tempo.finalizado_em.unwrap_or_else(Utc::now);
+        let iniciado_em = finalizado_em - chrono::Duration::minutes(duracao_minutos as i64);
+        estado.tempo.inicio = Some(iniciado_em);
+        
+        simulado.set_estado(&estado)?;
+        simulado.iniciado_em = Some(iniciado_em);
+        simulado.finalizado_em = Some(finalizado_em);
+        
+        let resultado = self.persistir_finalizacao(&simulado, &estado)?;
+        println!("📄 Folha de respostas da prova {} corrigida como simulado {}", simulado.prova_id, resultado.simulado_id);
+        
+        Ok(resultado)
+    }
+
+    /// Resultado do simulado. Simulados finalizados devolvem o snapshot gravado na
+    /// finalização (criado agora se ainda não existir); os em andamento são calculados na hora.
+    pub fn obter_resultado(&self, simulado_id: i64) -> Result<ResultadoSimulado> {
+        if let Some(resultado) = self.repo.buscar_resultado(simulado_id)? {
+            return Ok(resultado);
+        }
+        
+        let simulado = self.buscar_simulado(simulado_id)?;
         let estado = simulado.estado()?;
+        let mut resultado = self.montar_resultado(&simulado, &estado)?;
+        if estado.estado.esta_finalizado() {
+            self.repo.salvar_resultado(&resultado)?;
+        }
+        resultado.simulado_id = simulado_id;
+        Ok(resultado)
+    }
+
+    /// Recalcula o resultado com a versão atual da prova e substitui o snapshot.
+    /// Só acontece quando pedido explicitamente.
+    pub fn recalcular_resultado(&self, simulado_id: i64) -> Result<ResultadoSimulado> {
+        let simulado = self.buscar_simulado(simulado_id)?;
+        let estado = simulado.estado()?;
+        if !estado.estado.esta_finalizado() {
+            return Err(anyhow!("Simulado {} ainda não foi finalizado", simulado_id));
+        }
+        
+        let resultado = self.montar_resultado(&simulado, &estado)?;
+        self.repo.salvar_resultado(&resultado)?;
+        println!("♻️ Resultado do simulado {} recalculado: {:.1}", simulado_id, resultado.pontuacao);
+        Ok(resultado)
+    }
+
+    /// Grava o boletim do simulado em `caminho`. Sem formato explícito, usa a extensão do arquivo.
+    pub fn exportar_boletim(&self, simulado_id: i64, caminho: &Path, formato: Option<FormatoBoletim>) -> Result<()> {
+        let simulado = self.buscar_simulado(simulado_id)?;
+        let estado = simulado.estado()?;
+        if !estado.estado.esta_finalizado() {
+            return Err(anyhow!("Simulado {} ainda não foi finalizado", simulado_id));
+        }
+        let resultado = self.obter_resultado(simulado_id)?;
+        
+        let dados = DadosBoletim {
+            prova_id: &simulado.prova_id,
+            vestibular: &simulado.vestibular,
+            ano: simulado.ano,
+            data: simulado.finalizado_em.or(simulado.iniciado_em),
+            tempo_segundos: Some(estado.tempo.decorrido_segundos),
+            resultado: &resultado,
+        };
+        let conteudo = match formato.unwrap_or_else(|| FormatoBoletim::pela_extensao(caminho)) {
+            FormatoBoletim::Html => boletim::gerar_html(&dados).into_bytes(),
+            FormatoBoletim::Pdf => boletim::gerar_pdf(&dados),
+        };
+        
+        std::fs::write(caminho, conteudo)
+            .map_err(|e| anyhow!("Erro ao gravar {:?}: {}", caminho, e))?;
+        println!("🖨️ Boletim do simulado {} exportado para {:?}", simulado_id, caminho);
+        Ok(())
+    }
+
+    /// Exporta o histórico (ou a parte que passa no filtro) para planilhas.
+    /// Devolve quantos simulados foram exportados.
+    pub fn exportar_historico(&self, caminho: &Path, formato: Option<FormatoExportacao>, filtro: &FiltroExportacao) -> Result<usize> {
+        let mut exportados = Vec::new();
+        for simulado in self.repo.listar_todos()? {
+            if !filtro.aceita(simulado.id, &simulado.vestibular, &simulado.prova_id, simulado.iniciado_em) {
+                continue;
+            }
+            let estado = match simulado.estado() {
+                Ok(estado) => estado,
+                Err(e) => {
+                    println!("⚠️ Simulado {} fora da exportação, estado ilegível: {}", simulado.id, e);
+                    continue;
+                }
+            };
+            if estado.estado == EstadoSimulado::Abandonado && !filtro.incluir_abandonados {
+                continue;
+            }
+            
+            let resultado = if estado.estado.esta_finalizado() {
+                Some(self.obter_resultado(simulado.id)?)
+            } else {
+                None
+            };
+            
+            exportados.push(SimuladoExportado {
+                simulado_id: simulado.id,
+                estado: nome_serde(&estado.estado),
+                modo: nome_serde(&estado.modo),
+                iniciado_em: exportacao::formatar_data(simulado.iniciado_em),
+                finalizado_em: exportacao::formatar_data(simulado.finalizado_em),
+                pontuacao: resultado.as_ref().map(|r| r.pontuacao),
+                areas: resultado.iter()
+                    .flat_map(|r| &r.areas)
+                    .map(|a| AreaExportada { area_id: a.area_id.clone(), pontuacao_area: a.percentual })
+                    .collect(),
+                respostas: resultado.iter()
+                    .flat_map(|r| &r.detalhes)
+                    .map(|d| RespostaExportada {
+                        questao_id: d.questao_id.clone(),
+                        numero: d.numero,
+                        area_id: d.area_id.clone(),
+                        resposta: d.resposta_usuario.clone(),
+                        gabarito: d.gabarito.clone(),
+                        correta: d.acertou,
+                    })
+                    .collect(),
+                prova_id: simulado.prova_id,
+                vestibular: simulado.vestibular,
+                ano: simulado.ano,
+            });
+        }
+        
+        let conteudo = match formato.unwrap_or_else(|| FormatoExportacao::pela_extensao(caminho)) {
+            FormatoExportacao::Csv => exportacao::gerar_csv(&exportados),
+            FormatoExportacao::Json => exportacao::gerar_json(&exportados)?,
+        };
+        std::fs::write(caminho, conteudo)
+            .map_err(|e| anyhow!("Erro ao gravar {:?}: {}", caminho, e))?;
+        
+        println!("📤 {} simulados exportados para {:?}", exportados.len(), caminho);
+        Ok(exportados.len())
+    }
+
+    /// Quantas vezes a questão foi respondida e errada, e quais alternativas foram escolhidas
+    pub fn estatisticas_questao(&self, prova_id: &str, questao_id: &str) -> Result<EstatisticaQuestao> {
+        Ok(self.repo.estatisticas_questao(prova_id, questao_id)?)
+    }
+
+    /// Compara duas ou mais tentativas finalizadas da mesma prova, em ordem cronológica
+    pub fn comparar_tentativas(&self, simulado_ids: Vec<i64>) -> Result<ComparacaoTentativas> {
+        let mut ids = simulado_ids;
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.len() < 2 {
+            return Err(anyhow!("Informe ao menos duas tentativas para comparar"));
+        }
+        
+        let mut simulados = ids.iter()
+            .map(|id| self.buscar_simulado(*id))
+            .collect::<Result<Vec<_>>>()?;
+        simulados.sort_by_key(|s| (s.iniciado_em, s.id));
+        
+        let prova_id = simulados[0].prova_id.clone();
+        let mut tentativas = Vec::new();
+        for simulado in &simulados {
+            if simulado.prova_id != prova_id {
+                return Err(anyhow!("Simulado {} é da prova {}, não de {}", simulado.id, simulado.prova_id, prova_id));
+            }
+            let estado = simulado.estado()?;
+            if !estado.estado.esta_finalizado() {
+                return Err(anyhow!("Simulado {} ainda não foi finalizado", simulado.id));
+            }
+            
+            tentativas.push(Tentativa {
+                iniciado_em: simulado.iniciado_em,
+                finalizado_em: simulado.finalizado_em,
+                tempo_segundos: Some(estado.tempo.decorrido_segundos).filter(|s| *s > 0),
+                resultado: self.obter_resultado(simulado.id)?,
+            });
+        }
+        
+        Ok(comparacao::comparar(&prova_id, &tentativas))
+    }
+
+    /// Respostas usadas na correção: as linhas da tabela `resposta`. O `estado_json`
+    /// só guarda a cópia da interface. Uma folha em papel ainda não gravada não tem
+    /// linhas; nela as respostas vêm da própria folha, no estado recém-montado.
+    fn respostas_marcadas(&self, simulado: &Simulado, estado: &EstadoSimuladoCompleto) -> Result<HashMap<String, Option<String>>> {
+        if simulado.id == 0 {
+            return Ok(estado.respostas.clone());
+        }
+        Ok(self.repo.respostas(simulado.id)?)
+    }
+
+    fn montar_resultado(&self, simulado: &Simulado, estado: &EstadoSimuladoCompleto) -> Result<ResultadoSimulado> {
+        let simulado_id = simulado.id;
+        if estado.estado == EstadoSimulado::Abandonado {
+            return Err(anyhow!("Simulado {} foi abandonado e não possui resultado", simulado_id));
+        }
+        
         let prova_service = ProvaService::new(self.provas_dir.clone());
         
         let prova = prova_service.carregar(&simulado.prova_id)
             .map_err(|e| anyhow!("Erro ao carregar prova: {}", e))?;
+        let estatisticas = prova_service.carregar_estatisticas(&simulado.prova_id)
+            .map_err(|e| anyhow!("Erro ao carregar estatísticas da prova: {}", e))?;
 
         let mut acertos = 0;
+        let mut brancos = 0;
         let mut detalhes = Vec::new();
+        let mut areas: Vec<ResultadoArea> = Vec::new();
+        let mut itens_tri: BTreeMap<&str, Vec<(ParametrosTri, bool)>> = BTreeMap::new();
+        let tempo_registrado = !estado.tempo_questoes.is_empty();
+        let respostas = self.respostas_marcadas(simulado, estado)?;
         
         for questao in &prova.questoes {
-            let resposta_usuario = estado.respostas.get(&questao.id).cloned().flatten();
+            let resposta_usuario = respostas.get(&questao.id).cloned().flatten();
             let acertou = resposta_usuario.as_deref() == Some(&questao.resposta_correta);
+            let em_branco = resposta_usuario.is_none();
+            let eliminadas: Vec<String> = estado.eliminadas.get(&questao.id)
+                .map(|riscadas| riscadas.iter().cloned().collect())
+                .unwrap_or_default();
+            let eliminou_correta = eliminadas.contains(&questao.resposta_correta);
+            let tempo_segundos = tempo_registrado
+                .then(|| estado.tempo_questoes.get(&questao.id).copied().unwrap_or(0));
             
             if acertou {
                 acertos += 1;
+            } else if em_branco {
+                brancos += 1;
+            }
+            if let Some(parametros) = questao.tri {
+                itens_tri.entry(questao.area_id.as_str()).or_default().push((parametros, acertou));
+            }
+            
+            // Áreas na ordem em que aparecem na prova
+            let indice = match areas.iter().position(|a| a.area_id == questao.area_id) {
+                Some(indice) => indice,
+                None => {
+                    areas.push(ResultadoArea {
+                        area_id: questao.area_id.clone(),
+                        total: 0,
+                        acertos: 0,
+                        erros: 0,
+                        brancos: 0,
+                        percentual: 0.0,
+                        tempo_segundos: tempo_registrado.then_some(0),
+                    });
+                    areas.len() - 1
+                }
+            };
+            let area = &mut areas[indice];
+            area.total += 1;
+            if acertou {
+                area.acertos += 1;
+            } else if em_branco {
+                area.brancos += 1;
+            } else {
+                area.erros += 1;
+            }
+            if let (Some(total), Some(tempo)) = (area.tempo_segundos.as_mut(), tempo_segundos) {
+                *total += tempo;
             }
             
             detalhes.push(DetalheQuestao {
                 questao_id: questao.id.clone(),
                 numero: questao.numero,
+                area_id: questao.area_id.clone(),
                 resposta_usuario,
                 gabarito: questao.resposta_correta.clone(),
                 acertou,
+                eliminadas,
+                eliminou_correta,
+                tempo_segundos,
+                taxa_acerto_nacional: estatisticas.as_ref()
+                    .and_then(|e| e.questoes.get(&questao.id).copied()),
             });
         }
+        
+        for area in &mut areas {
+            area.percentual = percentual(area.acertos, area.total);
+        }
 
         let total = prova.total_questoes;
-        let erros = total - acertos;
-        let pontuacao = if total > 0 { (acertos as f64 / total as f64) * 100.0 } else { 0.0 };
+        let erros = total - acertos - brancos;
+        let mut pontuacao = percentual(acertos, total);
+        let tri = itens_tri.iter()
+            .map(|(area_id, respostas)| tri::estimar_eap(area_id, respostas))
+            .collect();
+        
+        let regras = prova_service.carregar_regras(&simulado.prova_id, &prova)
+            .map_err(|e| anyhow!("Erro ao carregar regras de pontuação: {}", e))?
+            .map(|regras| {
+                let itens: Vec<ItemPontuado> = detalhes.iter()
+                    .map(|d| ItemPontuado {
+                        questao_id: &d.questao_id,
+                        area_id: &d.area_id,
+                        acertou: d.acertou,
+                        em_branco: d.resposta_usuario.is_none(),
+                    })
+                    .collect();
+                regras.aplicar(&itens)
+            });
+        if let Some(aplicacao) = &regras {
+            pontuacao = aplicacao.pontuacao;
+        }
+
+        // Distribuições oficiais são por número de acertos, sem pesos nem penalidades
+        let comparativo = estatisticas.map(|e| Comparativo {
+            percentil: estatisticas::percentil(&e.distribuicao, acertos),
+            areas: areas.iter()
+                .filter_map(|area| {
+                    let distribuicao = e.areas.get(&area.area_id)?;
+                    Some(PercentilArea {
+                        area_id: area.area_id.clone(),
+                        percentil: estatisticas::percentil(distribuicao, area.acertos)?,
+                    })
+                })
+                .collect(),
+            fonte: e.fonte,
+        });
 
         Ok(ResultadoSimulado {
+            simulado_id,
+            modo: estado.modo,
             total_questoes: total,
             acertos,
             erros,
+            brancos,
             pontuacao,
+            areas,
             detalhes,
+            tri,
+            regras,
+            comparativo,
         })
     }
 
-    pub fn listar_simulados(&self) -> Result<Vec<SimuladoResumo>> {
-        let todos = self.repo.listar_todos()?;
+    /// Histórico de simulados. Os abandonados só aparecem quando pedidos explicitamente.
+    pub fn listar_simulados(&self, incluir_abandonados: bool) -> Result<Vec<SimuladoResumo>> {
+        let todos = self.repo.listar_com_edicao()?;
         
         let mut resumos = Vec::new();
-        for sim in todos {
-            let estado = sim.estado()?;
+        for (sim, vinculo) in todos {
+            // Um registro ilegível aparece marcado, sem esconder o resto do histórico
+            let estado = match sim.estado() {
+                Ok(estado) => estado,
+                Err(e) => {
+                    println!("⚠️ Estado ilegível no simulado {}: {}", sim.id, e);
+                    resumos.push(SimuladoResumo {
+                        id: sim.id,
+                        prova_id: sim.prova_id,
+                        vestibular: sim.vestibular,
+                        ano: sim.ano,
+                        iniciado_em: sim.iniciado_em.map(|dt| dt.to_rfc3339()),
+                        estado: "Corrompido".to_string(),
+                        modo: ModoSimulado::default(),
+                        erro: Some(e.to_string()),
+                        edicao_id: vinculo.map(|(edicao_id, _)| edicao_id),
+                        dia: vinculo.map(|(_, dia)| dia),
+                    });
+                    continue;
+                }
+            };
+            if estado.estado == EstadoSimulado::Abandonado && !incluir_abandonados {
+                continue;
+            }
+            resumos.push(SimuladoResumo {
+                id: sim.id,
+                prova_id: sim.prova_id,
+                vestibular: sim.vestibular,
+                ano: sim.ano,
+                iniciado_em: sim.iniciado_em.map(|dt| dt.to_rfc3339()),
+                estado: format!("{:?}", estado.estado),
+                modo: estado.modo,
+                erro: None,
+                edicao_id: vinculo.map(|(edicao_id, _)| edicao_id),
+                dia: vinculo.map(|(_, dia)| dia),
+            });
+        }
+        Ok(resumos)
+    }
+
+    /// Simulados que podem ser continuados pela tela inicial
+    pub fn listar_retomaveis(&self) -> Result<Vec<SimuladoResumo>> {
+        let todos = self.repo.listar_com_edicao()?;
+        
+        let mut resumos = Vec::new();
+        for (sim, vinculo) in todos {
+            let Ok(estado) = sim.estado() else {
+                continue;
+            };
+            if !estado.estado.esta_ativo() {
+                continue;
+            }
             resumos.push(SimuladoResumo {
                 id: sim.id,
+                prova_id: sim.prova_id,
                 vestibular: sim.vestibular,
                 ano: sim.ano,
                 iniciado_em: sim.iniciado_em.map(|dt| dt.to_rfc3339()),
                 estado: format!("{:?}", estado.estado),
+                modo: estado.modo,
+                erro: None,
+                edicao_id: vinculo.map(|(edicao_id, _)| edicao_id),
+                dia: vinculo.map(|(_, dia)| dia),
             });
         }
         Ok(resumos)
     }
 
+    /// Move o simulado para a lixeira. Ele some do histórico, das edições e das
+    /// estatísticas, mas pode ser restaurado até ser purgado.
     pub fn excluir(&self, simulado_id: i64) -> Result<()> {
-        unimplemented!("Implementar exclusão no repositório")
+        let simulado = self.buscar_simulado(simulado_id)?;
+        self.relogio.encerrar(simulado.id);
+        self.repo.marcar_excluido(simulado.id, Some(Utc::now()))?;
+        println!("🗑️ Simulado {} movido para a lixeira", simulado_id);
+        Ok(())
+    }
+
+    pub fn listar_lixeira(&self) -> Result<Vec<ItemLixeira>> {
+        let retencao = Duration::days(self.repo.dias_lixeira()? as i64);
+        self.repo.listar_lixeira()?
+            .into_iter()
+            .map(|sim| {
+                let expira_em = match sim.excluido_em {
+                    Some(dt) => Some(dt.checked_add_signed(retencao)
+                        .ok_or_else(|| anyhow!("Retenção da lixeira fora do intervalo de datas"))?
+                        .to_rfc3339()),
+                    None => None,
+                };
+                Ok(ItemLixeira {
+                    id: sim.id,
+                    prova_id: sim.prova_id,
+                    vestibular: sim.vestibular,
+                    ano: sim.ano,
+                    iniciado_em: sim.iniciado_em.map(|dt| dt.to_rfc3339()),
+                    excluido_em: sim.excluido_em.map(|dt| dt.to_rfc3339()),
+                    expira_em,
+                })
+            })
+            .collect()
+    }
+
+    pub fn restaurar(&self, simulado_id: i64) -> Result<()> {
+        let simulado = self.repo.buscar_na_lixeira(simulado_id)?
+            .ok_or_else(|| anyhow!("Simulado {} não está na lixeira", simulado_id))?;
+        self.repo.marcar_excluido(simulado.id, None)?;
+        println!("♻️ Simulado {} restaurado da lixeira", simulado_id);
+        Ok(())
+    }
+
+    /// Apaga um simulado recém-criado que não pôde ser usado (ex: vínculo com a edição falhou)
+    pub fn descartar_simulado(&self, simulado_id: i64) -> Result<()> {
+        self.relogio.encerrar(simulado_id);
+        self.repo.excluir_definitivamente(simulado_id)?;
+        Ok(())
+    }
+
+    /// Apaga de vez um simulado que já está na lixeira, com respostas e resultado
+    pub fn excluir_definitivamente(&self, simulado_id: i64) -> Result<()> {
+        let simulado = self.repo.buscar_na_lixeira(simulado_id)?
+            .ok_or_else(|| anyhow!("Simulado {} não está na lixeira", simulado_id))?;
+        self.repo.excluir_definitivamente(simulado.id)?;
+        println!("❌ Simulado {} excluído definitivamente", simulado_id);
+        Ok(())
+    }
+
+    /// Apaga o que está na lixeira há mais dias que a retenção configurada.
+    /// Chamado na inicialização. Retorna os IDs apagados.
+    pub fn purgar_lixeira(&self) -> Result<Vec<i64>> {
+        let dias = self.repo.dias_lixeira()?;
+        let limite = Utc::now()
+            .checked_sub_signed(Duration::days(dias as i64))
+            .ok_or_else(|| anyhow!("Retenção da lixeira inválida: {} dias", dias))?;
+        Ok(self.repo.purgar_lixeira(limite)?)
     }
-}
\ No newline at end of file
+
+    /// Política de recuperação configurada; valor ausente ou desconhecido usa o padrão
+    pub fn politica_recuperacao(&self) -> Result<PoliticaRecuperacao> {
+        Ok(self.repo.politica_recuperacao()?
+            .and_then(|nome| serde_json::from_value(serde_json::Value::String(nome)).ok())
+            .unwrap_or_default())
+    }
+
+    /// Passa a valer na próxima inicialização
+    pub fn configurar_politica_recuperacao(&self, politica: PoliticaRecuperacao) -> Result<()> {
+        self.repo.salvar_politica_recuperacao(&nome_serde(&politica))?;
+        println!("🩹 Política de recuperação: {:?}", politica);
+        Ok(())
+    }
+
+    pub fn dias_lixeira(&self) -> Result<u32> {
+        Ok(self.repo.dias_lixeira()?)
+    }
+
+    pub fn configurar_dias_lixeira(&self, dias: u32) -> Result<()> {
+        if !(1..=DIAS_LIXEIRA_MAXIMO).contains(&dias) {
+            return Err(anyhow!("A lixeira deve guardar os simulados de 1 a {} dias", DIAS_LIXEIRA_MAXIMO));
+        }
+        self.repo.salvar_dias_lixeira(dias)?;
+        println!("🗑️ Lixeira guarda simulados por {} dias", dias);
+        Ok(())
+    }
+}
+
+/// Nome do valor como aparece no JSON (ex: `FINALIZADO_POR_TEMPO`), estável entre versões
+fn nome_serde<T: serde::Serialize>(valor: &T) -> String {
+    serde_json::to_value(valor)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
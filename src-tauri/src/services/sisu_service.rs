@@ -0,0 +1,148 @@
+use crate::domain::sisu::{AreaEnem, BaseSisu, CursoSisu, SimulacaoCurso};
+use crate::services::edicao_service::EdicaoService;
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const ARQUIVO_CURSOS: &str = "cursos.json";
+
+/// Resultado da simulação do SISU para os cursos escolhidos
+#[derive(serde::Serialize)]
+pub struct SimulacaoSisu {
+    /// Notas usadas no cálculo, já identificadas por área
+    pub notas: HashMap<AreaEnem, f64>,
+    pub cursos: Vec<SimulacaoCurso>,
+    /// Observações sobre as notas (áreas não reconhecidas, notas estimadas...)
+    pub avisos: Vec<String>,
+    pub fonte: Option<String>,
+}
+
+/// Simulação do SISU a partir de uma base de cursos importada para a pasta de dados.
+/// Tudo local: atualizações chegam como arquivos, nunca pela rede.
+pub struct SisuService {
+    dados_dir: PathBuf,
+    edicoes: Arc<EdicaoService>,
+}
+
+impl SisuService {
+    pub fn new(dados_dir: PathBuf, edicoes: Arc<EdicaoService>) -> Self {
+        Self { dados_dir, edicoes }
+    }
+
+    fn caminho_base(&self) -> PathBuf {
+        self.dados_dir.join(ARQUIVO_CURSOS)
+    }
+
+    fn ler_base(caminho: &Path) -> Result<BaseSisu> {
+        let conteudo = fs::read_to_string(caminho)
+            .map_err(|e| anyhow!("Erro ao ler {:?}: {}", caminho, e))?;
+        let base: BaseSisu = serde_json::from_str(&conteudo)
+            .map_err(|e| anyhow!("Arquivo de cursos inválido {:?}: {}", caminho, e))?;
+        
+        for curso in &base.cursos {
+            if curso.pesos.values().any(|peso| *peso < 0.0) {
+                return Err(anyhow!("Curso {} tem peso negativo", curso.id));
+            }
+        }
+        Ok(base)
+    }
+
+    fn carregar_base(&self) -> Result<BaseSisu> {
+        let caminho = self.caminho_base();
+        if !caminho.exists() {
+            return Err(anyhow!("Nenhuma base de cursos importada. Importe um arquivo de cursos do SISU"));
+        }
+        Self::ler_base(&caminho)
+    }
+
+    /// Valida o arquivo e o copia para a pasta de dados, substituindo a base anterior.
+    /// Retorna quantos cursos foram importados.
+    pub fn importar_cursos(&self, origem: &Path) -> Result<usize> {
+        let base = Self::ler_base(origem)?;
+        
+        fs::create_dir_all(&self.dados_dir)
+            .map_err(|e| anyhow!("Erro ao criar {:?}: {}", self.dados_dir, e))?;
+        let json = serde_json::to_string_pretty(&base)?;
+        fs::write(self.caminho_base(), json)
+            .map_err(|e| anyhow!("Erro ao gravar base de cursos: {}", e))?;
+        
+        println!("🎓 Base do SISU importada de {:?}: {} cursos", origem, base.cursos.len());
+        Ok(base.cursos.len())
+    }
+
+    /// Cursos da base, opcionalmente filtrados por nome, instituição ou campus
+    pub fn listar_cursos(&self, busca: Option<&str>) -> Result<Vec<CursoSisu>> {
+        let base = self.carregar_base()?;
+        let Some(busca) = busca.map(str::to_lowercase).filter(|b| !b.is_empty()) else {
+            return Ok(base.cursos);
+        };
+        
+        Ok(base.cursos.into_iter()
+            .filter(|c| {
+                c.nome.to_lowercase().contains(&busca)
+                    || c.instituicao.to_lowercase().contains(&busca)
+                    || c.campus.as_deref().is_some_and(|campus| campus.to_lowercase().contains(&busca))
+            })
+            .collect())
+    }
+
+    /// Calcula a média ponderada de cada curso e compara com as notas de corte.
+    /// As notas vêm de `notas` (nome da área => nota 0–1000) ou dos dias concluídos
+    /// de uma edição; `redacao` completa ou substitui a nota de redação.
+    pub fn simular(
+        &self,
+        curso_ids: &[String],
+        notas: Option<HashMap<String, f64>>,
+        edicao_id: Option<i64>,
+        redacao: Option<f64>,
+    ) -> Result<SimulacaoSisu> {
+        let base = self.carregar_base()?;
+        let mut avisos = Vec::new();
+        let mut notas_area: HashMap<AreaEnem, f64> = HashMap::new();
+        
+        match (notas, edicao_id) {
+            (Some(_), Some(_)) => return Err(anyhow!("Informe as notas ou a edição, não os dois")),
+            (Some(notas), None) => {
+                for (nome, nota) in notas {
+                    match AreaEnem::identificar(&nome) {
+                        Some(area) => { notas_area.insert(area, nota); }
+                        None => avisos.push(format!("Área não reconhecida: {}", nome)),
+                    }
+                }
+            }
+            (None, Some(edicao_id)) => {
+                let edicao = self.edicoes.notas_por_area(edicao_id)?;
+                avisos.extend(edicao.avisos);
+                for nota in edicao.notas {
+                    let Some(area) = AreaEnem::identificar(&nota.area_id) else {
+                        avisos.push(format!("Área não reconhecida: {}", nota.area_id));
+                        continue;
+                    };
+                    if !nota.estimada_por_tri {
+                        avisos.push(format!("Nota de {} estimada pelo percentual de acertos (prova sem parâmetros TRI)", nota.area_id));
+                    }
+                    notas_area.insert(area, nota.nota);
+                }
+            }
+            (None, None) => return Err(anyhow!("Informe as notas por área ou uma edição do ENEM")),
+        }
+        if let Some(redacao) = redacao {
+            notas_area.insert(AreaEnem::Redacao, redacao);
+        }
+        if let Some((area, nota)) = notas_area.iter().find(|(_, nota)| !(0.0..=1000.0).contains(*nota)) {
+            return Err(anyhow!("Nota de {:?} fora da escala 0–1000: {}", area, nota));
+        }
+        
+        let mut cursos = Vec::new();
+        for id in curso_ids {
+            let curso = base.cursos.iter()
+                .find(|c| &c.id == id)
+                .ok_or_else(|| anyhow!("Curso {} não está na base do SISU", id))?;
+            cursos.push(SimulacaoCurso::calcular(curso, &notas_area));
+        }
+        
+        Ok(SimulacaoSisu { notas: notas_area, cursos, avisos, fonte: base.fonte })
+    }
+}
@@ -1 +1,2 @@
-pub mod transitions;
\ No newline at end of file
+pub mod transitions;
+pub mod relogio;
\ No newline at end of file
@@ -0,0 +1,61 @@
+// relogio.rs
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Diferença tolerada entre o relógio monotônico e o relógio do sistema
+/// antes de registrarmos um salto (ajuste manual, fuso/horário de verão, suspensão).
+const TOLERANCIA_SALTO_SEGUNDOS: i64 = 5;
+
+struct Ancora {
+    inicio_intervalo: DateTime<Utc>,
+    instante: Instant,
+}
+
+/// Mede a duração do intervalo ativo de cada simulado com um relógio monotônico.
+/// O relógio de parede só é usado quando não há âncora nesta execução do app
+/// (ex: primeira medição após reiniciar), e serve de conferência nas demais.
+#[derive(Default)]
+pub struct RelogioSessao {
+    ancoras: Mutex<HashMap<i64, Ancora>>,
+}
+
+impl RelogioSessao {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Segundos decorridos no intervalo ativo iniciado em `inicio_intervalo`
+    pub fn segundos_intervalo(&self, simulado_id: i64, inicio_intervalo: DateTime<Utc>) -> u32 {
+        let parede = Utc::now().signed_duration_since(inicio_intervalo).num_seconds();
+        let mut ancoras = self.ancoras.lock().unwrap();
+
+        if let Some(ancora) = ancoras.get(&simulado_id) {
+            if ancora.inicio_intervalo == inicio_intervalo {
+                let monotonico = ancora.instante.elapsed().as_secs() as i64;
+                if (monotonico - parede).abs() > TOLERANCIA_SALTO_SEGUNDOS {
+                    println!("⚠️ Salto de relógio detectado no simulado {}: monotônico={}s, relógio do sistema={}s",
+                        simulado_id, monotonico, parede);
+                }
+                return monotonico.max(0) as u32;
+            }
+        }
+
+        if parede < 0 {
+            println!("⚠️ Início do intervalo no futuro para o simulado {} ({}s): relógio do sistema voltou?",
+                simulado_id, parede);
+        }
+        let parede = parede.max(0);
+        let instante = Instant::now()
+            .checked_sub(Duration::from_secs(parede as u64))
+            .unwrap_or_else(Instant::now);
+        ancoras.insert(simulado_id, Ancora { inicio_intervalo, instante });
+        parede as u32
+    }
+
+    /// Descarta a âncora quando o intervalo ativo termina (pausa ou finalização)
+    pub fn encerrar(&self, simulado_id: i64) {
+        self.ancoras.lock().unwrap().remove(&simulado_id);
+    }
+}
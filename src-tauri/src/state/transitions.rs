@@ -1,7 +1,7 @@
 // transitions.rs
 use crate::domain::estado::{EstadoSimulado, ModoTempo, EstadoSimuladoCompleto};
 use thiserror::Error;
-use chrono::{Utc, Duration};
+use chrono::Utc;
 
 #[derive(Debug, Error)]
 pub enum TransicaoErro {
@@ -11,34 +11,75 @@ pub enum TransicaoErro {
     TempoNaoIniciado,
 }
 
+// O tempo ativo é a soma de intervalos: `acumulado_segundos` guarda os intervalos
+// encerrados e `intervalo_segundos` (medido pelo `RelogioSessao`, monotônico) é a
+// duração do intervalo em curso. Nada aqui subtrai datas do relógio do sistema,
+// então ajustes de relógio não adicionam nem removem tempo de prova.
+
 pub fn iniciar(estado: &mut EstadoSimuladoCompleto) -> Result<(), TransicaoErro> {
     if estado.estado != EstadoSimulado::NaoIniciado {
         return Err(TransicaoErro::EstadoInvalido);
     }
     
-    estado.tempo.inicio = Some(Utc::now());
+    let agora = Utc::now();
+    estado.tempo.inicio = Some(agora);
+    estado.tempo.inicio_intervalo = Some(agora);
+    estado.tempo.acumulado_segundos = 0;
+    estado.tempo.decorrido_segundos = 0;
+    estado.tempo.questao_desde_segundos = 0;
+    estado.tempo.ultimo_heartbeat = Some(agora);
     estado.estado = EstadoSimulado::EmAndamento;
     
     println!("⏰ Transição iniciar: tempo.inicio definido para {:?}", estado.tempo.inicio);
     Ok(())
 }
 
-pub fn pausar(estado: &mut EstadoSimuladoCompleto) -> Result<(), TransicaoErro> {
+/// Atualiza `decorrido_segundos` com a duração do intervalo em curso
+pub fn atualizar_decorrido(estado: &mut EstadoSimuladoCompleto, intervalo_segundos: u32) -> Result<(), TransicaoErro> {
     if estado.estado != EstadoSimulado::EmAndamento {
-        return Err(TransicaoErro::EstadoInvalido);
+        return Ok(());
+    }
+    if estado.tempo.inicio_intervalo.is_none() {
+        return Err(TransicaoErro::TempoNaoIniciado);
     }
     
-    // CALCULA E SALVA O TEMPO DECORRIDO ANTES DE PAUSAR
-    if let Some(inicio) = estado.tempo.inicio {
-        let agora = Utc::now();
-        let decorrido_total = agora.signed_duration_since(inicio).num_seconds();
-        estado.tempo.decorrido_segundos = decorrido_total.max(0) as u32;
-        
-        println!("⏸️ Pausando: inicio={:?}, decorrido={}", inicio, estado.tempo.decorrido_segundos);
-    } else {
+    estado.tempo.decorrido_segundos = estado.tempo.acumulado_segundos.saturating_add(intervalo_segundos);
+    Ok(())
+}
+
+/// Encerra o intervalo ativo, somando sua duração ao acumulado
+fn encerrar_intervalo(estado: &mut EstadoSimuladoCompleto, intervalo_segundos: u32) {
+    if estado.tempo.inicio_intervalo.take().is_some() {
+        estado.tempo.acumulado_segundos = estado.tempo.acumulado_segundos.saturating_add(intervalo_segundos);
+    }
+    estado.tempo.decorrido_segundos = estado.tempo.acumulado_segundos;
+    registrar_tempo_questao(estado);
+}
+
+/// Credita à questão atual o tempo ativo desde que o aluno chegou nela.
+/// Espera `decorrido_segundos` atualizado; chamado antes de trocar de questão.
+pub fn registrar_tempo_questao(estado: &mut EstadoSimuladoCompleto) {
+    let agora = estado.tempo.decorrido_segundos;
+    let desde = estado.tempo.questao_desde_segundos;
+    if agora > desde {
+        *estado.tempo_questoes.entry(estado.progresso.questao_atual.clone()).or_insert(0) += agora - desde;
+    }
+    estado.tempo.questao_desde_segundos = agora;
+}
+
+pub fn pausar(estado: &mut EstadoSimuladoCompleto, intervalo_segundos: u32) -> Result<(), TransicaoErro> {
+    if estado.estado != EstadoSimulado::EmAndamento {
+        return Err(TransicaoErro::EstadoInvalido);
+    }
+    if estado.tempo.inicio_intervalo.is_none() {
         return Err(TransicaoErro::TempoNaoIniciado);
     }
     
+    // CALCULA E SALVA O TEMPO DECORRIDO ANTES DE PAUSAR
+    encerrar_intervalo(estado, intervalo_segundos);
+    
+    println!("⏸️ Pausando: intervalo={}s, decorrido={}", intervalo_segundos, estado.tempo.decorrido_segundos);
+    
     estado.tempo.pausado_em = Some(Utc::now());
     estado.estado = EstadoSimulado::Pausado;
     Ok(())
@@ -48,25 +89,26 @@ pub fn retomar(estado: &mut EstadoSimuladoCompleto) -> Result<(), TransicaoErro>
     if estado.estado != EstadoSimulado::Pausado {
         return Err(TransicaoErro::EstadoInvalido);
     }
+    if estado.tempo.inicio.is_none() {
+        return Err(TransicaoErro::TempoNaoIniciado);
+    }
     
-    let pausado_em = estado.tempo.pausado_em.ok_or(TransicaoErro::TempoNaoIniciado)?;
-    let inicio = estado.tempo.inicio.ok_or(TransicaoErro::TempoNaoIniciado)?;
-    
-    // CALCULA A DURAÇÃO DA PAUSA
-    let duracao_pausa = Utc::now().signed_duration_since(pausado_em);
-    
-    // ATUALIZA O TEMPO DE INÍCIO PARA COMPENSAR O TEMPO DE PAUSA
-    estado.tempo.inicio = Some(inicio + duracao_pausa);
+    // ABRE UM NOVO INTERVALO ATIVO; O TEMPO DE PAUSA SIMPLESMENTE NÃO É SOMADO
+    let agora = Utc::now();
+    estado.tempo.inicio_intervalo = Some(agora);
     estado.tempo.pausado_em = None;
+    estado.tempo.ultimo_heartbeat = Some(agora);
     
-    println!("▶️ Retomando: novo_inicio={:?}", estado.tempo.inicio);
+    println!("▶️ Retomando: acumulado={}s", estado.tempo.acumulado_segundos);
     
     estado.estado = EstadoSimulado::EmAndamento;
     Ok(())
 }
-pub fn finalizar(estado: &mut EstadoSimuladoCompleto) -> Result<(), TransicaoErro> {
+
+pub fn finalizar(estado: &mut EstadoSimuladoCompleto, intervalo_segundos: u32) -> Result<(), TransicaoErro> {
     match estado.estado {
         EstadoSimulado::EmAndamento | EstadoSimulado::Pausado => {
+            encerrar_intervalo(estado, intervalo_segundos);
             estado.tempo.finalizado_em = Some(Utc::now());
             estado.estado = EstadoSimulado::Finalizado;
             Ok(())
@@ -75,28 +117,66 @@ pub fn finalizar(estado: &mut EstadoSimuladoCompleto) -> Result<(), TransicaoErr
     }
 }
 
-pub fn verificar_expiracao_tempo(estado: &mut EstadoSimuladoCompleto) -> Result<(), TransicaoErro> {
+/// Desiste da tentativa. O tempo ativo até aqui é preservado para consulta.
+pub fn abandonar(estado: &mut EstadoSimuladoCompleto, intervalo_segundos: u32) -> Result<(), TransicaoErro> {
+    if !estado.estado.pode_abandonar() {
+        return Err(TransicaoErro::EstadoInvalido);
+    }
+    
+    encerrar_intervalo(estado, intervalo_segundos);
+    estado.tempo.pausado_em = None;
+    estado.estado = EstadoSimulado::Abandonado;
+    Ok(())
+}
+
+pub fn verificar_expiracao_tempo(estado: &mut EstadoSimuladoCompleto, intervalo_segundos: u32) -> Result<(), TransicaoErro> {
     if estado.estado != EstadoSimulado::EmAndamento {
         return Ok(());
     }
 
-    let inicio = estado.tempo.inicio.ok_or(TransicaoErro::TempoNaoIniciado)?;
-    let agora = Utc::now();
-    let decorrido = (agora - inicio).num_seconds().max(0) as u32;
-    estado.tempo.decorrido_segundos = decorrido;
+    atualizar_decorrido(estado, intervalo_segundos)?;
+    let decorrido = estado.tempo.decorrido_segundos;
 
     let limite_segundos = estado.tempo.limite_minutos as u32 * 60;
     if limite_segundos > 0 && decorrido >= limite_segundos {
         if estado.configuracoes.permitir_ultrapassar_tempo {
             estado.modo_tempo = ModoTempo::Livre;
         } else {
-            finalizar_por_tempo(estado);
+            finalizar_por_tempo(estado, intervalo_segundos);
         }
     }
     Ok(())
 }
 
-fn finalizar_por_tempo(estado: &mut EstadoSimuladoCompleto) {
+fn finalizar_por_tempo(estado: &mut EstadoSimuladoCompleto, intervalo_segundos: u32) {
+    encerrar_intervalo(estado, intervalo_segundos);
     estado.tempo.finalizado_em = Some(Utc::now());
     estado.estado = EstadoSimulado::FinalizadoPorTempo;
-}
\ No newline at end of file
+}
+
+/// Verifica quais limiares de alerta foram cruzados desde a última verificação.
+/// Cada limiar dispara uma única vez; se vários forem cruzados de uma vez
+/// (ex: após uma recuperação), todos são retornados, do maior para o menor.
+pub fn verificar_alertas(estado: &mut EstadoSimuladoCompleto) -> Vec<u32> {
+    if estado.estado != EstadoSimulado::EmAndamento {
+        return Vec::new();
+    }
+
+    let limite_segundos = estado.tempo.limite_minutos as u32 * 60;
+    if limite_segundos == 0 {
+        return Vec::new();
+    }
+    let restante = limite_segundos.saturating_sub(estado.tempo.decorrido_segundos);
+
+    let mut cruzados: Vec<u32> = estado.configuracoes.alertas_minutos.iter()
+        .copied()
+        .filter(|limiar| limiar.saturating_mul(60) < limite_segundos)
+        .filter(|limiar| restante <= limiar.saturating_mul(60))
+        .filter(|limiar| !estado.tempo.alertas_disparados.contains(limiar))
+        .collect();
+    cruzados.sort_unstable_by(|a, b| b.cmp(a));
+    cruzados.dedup();
+
+    estado.tempo.alertas_disparados.extend(&cruzados);
+    cruzados
+}